@@ -0,0 +1,83 @@
+#![feature(test)]
+extern crate test;
+
+use ecs::*;
+use test::Bencher;
+
+#[derive(Component)]
+#[allow(unused)]
+struct Position(f32, f32, f32);
+
+#[derive(Component)]
+#[allow(unused)]
+struct Velocity(f32, f32, f32);
+
+#[derive(Component)]
+#[allow(unused)]
+struct Stunned(u32);
+
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+#[allow(unused)]
+struct Highlighted(u32);
+
+const ENTITIES: usize = 10_000;
+
+fn world() -> (World, Vec<Entity>) {
+    let mut world = World::new(1).unwrap();
+    let entities = world.spawn_batch((0..ENTITIES).map(|i| {
+        let f = i as f32;
+        (Position(f, f, f), Velocity(f, f, f))
+    }));
+    (world, entities)
+}
+
+#[bench]
+fn add_remove_table(b: &mut Bencher) {
+    let (mut world, entities) = world();
+    b.iter(|| {
+        for entity in entities.iter().copied() {
+            world.set_component(entity, Stunned(1));
+        }
+        for entity in entities.iter().copied() {
+            world.remove_component::<Stunned>(entity);
+        }
+    });
+}
+
+#[bench]
+fn add_remove_sparse_set(b: &mut Bencher) {
+    let (mut world, entities) = world();
+    b.iter(|| {
+        for entity in entities.iter().copied() {
+            world.set_component(entity, Highlighted(1));
+        }
+        for entity in entities.iter().copied() {
+            world.remove_component::<Highlighted>(entity);
+        }
+    });
+}
+
+#[bench]
+fn toggle_table(b: &mut Bencher) {
+    let (mut world, entities) = world();
+    b.iter(|| {
+        for entity in entities.iter().copied() {
+            world.set_component(entity, Stunned(1));
+            world.remove_component::<Stunned>(entity);
+        }
+    });
+}
+
+#[bench]
+fn add_remove_bundle(b: &mut Bencher) {
+    let (mut world, entities) = world();
+    b.iter(|| {
+        for entity in entities.iter().copied() {
+            world.entity_mut(entity).insert((Stunned(1), Highlighted(1)));
+        }
+        for entity in entities.iter().copied() {
+            world.remove_bundle::<(Stunned, Highlighted)>(entity);
+        }
+    });
+}
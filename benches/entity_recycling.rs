@@ -0,0 +1,81 @@
+#![feature(test)]
+extern crate test;
+
+use ecs::*;
+use test::Bencher;
+
+#[derive(Component)]
+struct Position(f32, f32);
+
+const ENTITIES: usize = 10_000;
+
+// despawns every other entity and respawns it, the way short lived entities churn in a game loop
+fn churn(b: &mut Bencher, policy: EntityRecyclingPolicy) {
+    let mut world = World::default();
+    world.set_entity_recycling_policy(policy);
+    let mut entities: Vec<Entity> = (0..ENTITIES).map(|_| world.spawn(Position(0.0, 0.0))).collect();
+    let mut schedule = Schedule::default();
+    b.iter(|| {
+        for entity in entities.iter_mut().step_by(2) {
+            world.despawn(*entity);
+            *entity = world.spawn(Position(1.0, 1.0));
+        }
+        schedule.run(&mut world);
+        test::black_box(&entities);
+    });
+}
+
+#[bench]
+fn churn_lifo(b: &mut Bencher) {
+    churn(b, EntityRecyclingPolicy::Lifo);
+}
+
+#[bench]
+fn churn_fifo(b: &mut Bencher) {
+    churn(b, EntityRecyclingPolicy::Fifo);
+}
+
+#[bench]
+fn churn_quarantine_spawns(b: &mut Bencher) {
+    churn(b, EntityRecyclingPolicy::QuarantineSpawns(1024));
+}
+
+#[bench]
+fn churn_quarantine_schedule_runs(b: &mut Bencher) {
+    churn(b, EntityRecyclingPolicy::QuarantineScheduleRuns(2));
+}
+
+// iterates a query after churn, recycling order decides how scattered the dense storage gets
+fn churn_then_iterate(b: &mut Bencher, policy: EntityRecyclingPolicy) {
+    let mut world = World::default();
+    world.set_entity_recycling_policy(policy);
+    let mut entities: Vec<Entity> = (0..ENTITIES).map(|_| world.spawn(Position(0.0, 0.0))).collect();
+    for round in 0..8 {
+        for entity in entities.iter_mut().skip(round).step_by(3) {
+            world.despawn(*entity);
+            *entity = world.spawn(Position(1.0, 1.0));
+        }
+    }
+    b.iter(|| {
+        let mut sum = 0.0;
+        for position in world.query::<&Position>().iter() {
+            sum += position.0 + position.1;
+        }
+        test::black_box(sum);
+    });
+}
+
+#[bench]
+fn iterate_lifo(b: &mut Bencher) {
+    churn_then_iterate(b, EntityRecyclingPolicy::Lifo);
+}
+
+#[bench]
+fn iterate_fifo(b: &mut Bencher) {
+    churn_then_iterate(b, EntityRecyclingPolicy::Fifo);
+}
+
+#[bench]
+fn iterate_quarantine_spawns(b: &mut Bencher) {
+    churn_then_iterate(b, EntityRecyclingPolicy::QuarantineSpawns(1024));
+}
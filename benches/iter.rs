@@ -0,0 +1,85 @@
+#![feature(test)]
+extern crate test;
+
+use ecs::*;
+use test::Bencher;
+
+#[derive(Component)]
+struct Position(f32, f32, f32);
+
+#[derive(Component)]
+struct Velocity(f32, f32, f32);
+
+#[derive(Component)]
+#[allow(unused)]
+struct Stunned(u32);
+
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+#[allow(unused)]
+struct Highlighted(u32);
+
+const ENTITIES: usize = 10_000;
+
+fn world() -> World {
+    let mut world = World::new(1).unwrap();
+    world.spawn_batch((0..ENTITIES).map(|i| {
+        let f = i as f32;
+        (Position(f, f, f), Velocity(f, f, f))
+    }));
+    world
+}
+
+#[bench]
+fn iter(b: &mut Bencher) {
+    let mut world = world();
+    b.iter(|| {
+        let query = world.query::<(&Position, &Velocity)>();
+        for (position, velocity) in query.iter() {
+            test::black_box(position.0 + velocity.0);
+        }
+    });
+}
+
+#[bench]
+fn iter_mut(b: &mut Bencher) {
+    let mut world = world();
+    b.iter(|| {
+        let mut query = world.query::<(&mut Position, &Velocity)>();
+        for (position, velocity) in query.iter_mut() {
+            position.0 += velocity.0;
+            position.1 += velocity.1;
+            position.2 += velocity.2;
+        }
+    });
+}
+
+#[bench]
+fn iter_mut_fragmented(b: &mut Bencher) {
+    let mut world = world();
+    let entities = world.query::<Entity>().iter().collect::<Vec<_>>();
+    for entity in entities.iter().copied().step_by(2) {
+        world.set_component(entity, Stunned(1));
+    }
+    b.iter(|| {
+        let mut query = world.query::<(&mut Position, &Velocity)>();
+        for (position, velocity) in query.iter_mut() {
+            position.0 += velocity.0;
+        }
+    });
+}
+
+#[bench]
+fn iter_mut_sparse_set(b: &mut Bencher) {
+    let mut world = world();
+    let entities = world.query::<Entity>().iter().collect::<Vec<_>>();
+    for entity in entities.iter().copied().step_by(2) {
+        world.set_component(entity, Highlighted(1));
+    }
+    b.iter(|| {
+        let mut query = world.query::<(&mut Position, &Velocity, &Highlighted)>();
+        for (position, velocity, _) in query.iter_mut() {
+            position.0 += velocity.0;
+        }
+    });
+}
@@ -0,0 +1,72 @@
+#![feature(test)]
+extern crate test;
+
+use ecs::*;
+use test::Bencher;
+
+#[derive(Component)]
+#[allow(unused)]
+struct Position(f32, f32, f32);
+
+#[derive(Component)]
+#[allow(unused)]
+struct Velocity(f32, f32, f32);
+
+#[derive(Component)]
+#[allow(unused)]
+struct Health(u32);
+
+const ENTITIES: usize = 10_000;
+
+fn bundle(i: usize) -> (Position, Velocity, Health) {
+    let f = i as f32;
+    (Position(f, f, f), Velocity(f, f, f), Health(i as u32))
+}
+
+#[bench]
+fn spawn_single(b: &mut Bencher) {
+    b.iter(|| {
+        let mut world = World::new(1).unwrap();
+        for i in 0..ENTITIES {
+            test::black_box(world.spawn(bundle(i)));
+        }
+        world
+    });
+}
+
+#[bench]
+fn spawn_batch(b: &mut Bencher) {
+    b.iter(|| {
+        let mut world = World::new(1).unwrap();
+        test::black_box(world.spawn_batch((0..ENTITIES).map(bundle)));
+        world
+    });
+}
+
+#[bench]
+fn commands_spawn_single(b: &mut Bencher) {
+    b.iter(|| {
+        let mut world = World::new(1).unwrap();
+        let mut schedule = Schedule::default();
+        schedule.add_system(|mut commands: Commands| {
+            for i in 0..ENTITIES {
+                test::black_box(commands.spawn(bundle(i)));
+            }
+        });
+        schedule.run(&mut world);
+        world
+    });
+}
+
+#[bench]
+fn commands_spawn_batch(b: &mut Bencher) {
+    b.iter(|| {
+        let mut world = World::new(1).unwrap();
+        let mut schedule = Schedule::default();
+        schedule.add_system(|mut commands: Commands| {
+            test::black_box(commands.spawn_batch((0..ENTITIES).map(bundle)));
+        });
+        schedule.run(&mut world);
+        world
+    });
+}
@@ -1,15 +1,102 @@
 use proc_macro::TokenStream;
 use syn::DeriveInput;
 
-#[proc_macro_derive(Component)]
+#[proc_macro_derive(Component, attributes(require, component))]
 pub fn component_derive_macro(item: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(item).unwrap();
     let ident = &ast.ident;
     let generics = &ast.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut required_components = Vec::new();
+    for attr in ast.attrs.iter().filter(|p| p.path().is_ident("require")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            required_components.push(meta.path);
+            Ok(())
+        });
+        if let Err(err) = parsed {
+            return err.to_compile_error().into();
+        }
+    }
+
+    let mut mutability = quote::quote! { ecs::Mutable };
+    let mut storage_type = quote::quote! { ecs::StorageType::Table };
+    for attr in ast.attrs.iter().filter(|p| p.path().is_ident("component")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("immutable") {
+                mutability = quote::quote! { ecs::Immutable };
+                return Ok(());
+            }
+            if meta.path.is_ident("storage") {
+                let storage = meta.value()?.parse::<syn::LitStr>()?;
+                storage_type = match storage.value().as_str() {
+                    "Table" => quote::quote! { ecs::StorageType::Table },
+                    "SparseSet" => quote::quote! { ecs::StorageType::SparseSet },
+                    _ => return Err(syn::Error::new_spanned(storage, "expected \"Table\" or \"SparseSet\"")),
+                };
+                return Ok(());
+            }
+            Err(meta.error("unknown component attribute"))
+        });
+        if let Err(err) = parsed {
+            return err.to_compile_error().into();
+        }
+    }
 
     quote::quote! {
-        impl #impl_generics ecs::Component for #ident #ty_generics #where_clause {}
+        impl #impl_generics ecs::Component for #ident #ty_generics #where_clause {
+            type Mutability = #mutability;
+            const STORAGE_TYPE: ecs::StorageType = #storage_type;
+            fn register_required_components(required: &mut ecs::RequiredComponents) {
+                #(required.require::<#required_components>();)*
+            }
+        }
+    }.into()
+}
+
+#[proc_macro_derive(Bundle)]
+pub fn bundle_derive_macro(item: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse(item).unwrap();
+    let ident = &ast.ident;
+    let generics = &ast.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let syn::Data::Struct(data) = &ast.data else {
+        return syn::Error::new_spanned(ident, "Bundle can only be derived for structs").to_compile_error().into();
+    };
+    let members: Vec<syn::Member> = data.fields.members().collect();
+    let types: Vec<&syn::Type> = data.fields.iter().map(|field| &field.ty).collect();
+
+    quote::quote! {
+        impl #impl_generics ecs::ComponentBundle for #ident #ty_generics #where_clause {
+            fn signature(world: &mut ecs::World) -> ecs::Signature {
+                let mut signature = ecs::Signature::new();
+                #(signature |= &<#types as ecs::ComponentBundle>::signature(world);)*
+                signature
+            }
+
+            fn insert(self, entity: ecs::Entity, world: &mut ecs::World) {
+                #(<#types as ecs::ComponentBundle>::insert(self.#members, entity, world);)*
+            }
+
+            fn insert_if_new(self, entity: ecs::Entity, world: &mut ecs::World) {
+                #(<#types as ecs::ComponentBundle>::insert_if_new(self.#members, entity, world);)*
+            }
+
+            fn reserve(world: &mut ecs::World, additional: usize) {
+                #(<#types as ecs::ComponentBundle>::reserve(world, additional);)*
+            }
+
+            unsafe fn write_components(self, entity: ecs::Entity, world: &mut ecs::World) {
+                unsafe { #(<#types as ecs::ComponentBundle>::write_components(self.#members, entity, world);)* }
+            }
+
+            fn trigger_added(entity: ecs::Entity, world: &mut ecs::World) {
+                #(<#types as ecs::ComponentBundle>::trigger_added(entity, world);)*
+            }
+
+            unsafe fn take_components(entity: ecs::Entity, world: &mut ecs::World) -> Self {
+                unsafe { Self { #(#members: <#types as ecs::ComponentBundle>::take_components(entity, world),)* } }
+            }
+        }
     }.into()
 }
 
@@ -1,71 +1,71 @@
 use std::fmt::Display;
-use crate::bitmap::Bitmap;
+use crate::{Signature, bitset::BitSet};
 
 #[derive(Default, Clone)]
 pub struct Access {
-    resource_immutable: Bitmap,
-    resource_mutable: Bitmap,
-    component_immutable: Bitmap,
-    component_mutable: Bitmap,
+    resource_immutable: BitSet,
+    resource_mutable: BitSet,
+    component_immutable: Signature,
+    component_mutable: Signature,
 }
 
 impl Access {
     pub fn conflicts(&self, other: &Self) -> bool {
-        !( (self.resource_immutable & other.resource_mutable).is_zero() &&
-            (other.resource_immutable & self.resource_mutable).is_zero() &&
-            (self.resource_mutable & other.resource_mutable).is_zero() &&
-            (self.component_immutable & other.component_mutable).is_zero() &&
-            (other.component_immutable & self.component_mutable).is_zero() &&
-            (self.component_mutable & other.component_mutable).is_zero() 
+        !( self.resource_immutable.is_disjoint(&other.resource_mutable) &&
+            other.resource_immutable.is_disjoint(&self.resource_mutable) &&
+            self.resource_mutable.is_disjoint(&other.resource_mutable) &&
+            self.component_immutable.is_disjoint(&other.component_mutable) &&
+            other.component_immutable.is_disjoint(&self.component_mutable) &&
+            self.component_mutable.is_disjoint(&other.component_mutable)
         )
     }
 
     pub fn is_compatible(&self, other: &Self) -> bool {
-        (self.resource_immutable & other.resource_mutable).is_zero() &&
-        (other.resource_immutable & self.resource_mutable).is_zero() &&
-        (self.resource_mutable & other.resource_mutable).is_zero() &&
-        (self.component_immutable & other.component_mutable).is_zero() &&
-        (other.component_immutable & self.component_mutable).is_zero() &&
-        (self.component_mutable & other.component_mutable).is_zero() 
+        self.resource_immutable.is_disjoint(&other.resource_mutable) &&
+        other.resource_immutable.is_disjoint(&self.resource_mutable) &&
+        self.resource_mutable.is_disjoint(&other.resource_mutable) &&
+        self.component_immutable.is_disjoint(&other.component_mutable) &&
+        other.component_immutable.is_disjoint(&self.component_mutable) &&
+        self.component_mutable.is_disjoint(&other.component_mutable)
     }
 
     // Both Accesses must be compatible with each other
     pub fn join(&mut self, other: &Self) {
         debug_assert!(!self.conflicts(other));
-        self.resource_immutable |= other.resource_immutable;
-        self.resource_mutable |= other.resource_mutable;
-        self.component_immutable |= other.component_immutable;
-        self.component_mutable |= other.component_mutable;
+        self.resource_immutable |= &other.resource_immutable;
+        self.resource_mutable |= &other.resource_mutable;
+        self.component_immutable |= &other.component_immutable;
+        self.component_mutable |= &other.component_mutable;
     }
 
-    pub fn resource_immutable(&self) -> &Bitmap {
+    pub fn resource_immutable(&self) -> &BitSet {
         &self.resource_immutable
     }
 
-    pub fn resource_mutable(&self) -> &Bitmap {
+    pub fn resource_mutable(&self) -> &BitSet {
         &self.resource_mutable
     }
 
-    pub fn component_immutable(&self) -> &Bitmap {
+    pub fn component_immutable(&self) -> &Signature {
         &self.component_immutable
     }
 
-    pub fn component_mutable(&self) -> &Bitmap {
+    pub fn component_mutable(&self) -> &Signature {
         &self.component_mutable
     }
 
     pub fn clear(&mut self) {
-        self.resource_immutable = Bitmap::new();
-        self.resource_mutable = Bitmap::new();
-        self.component_immutable = Bitmap::new();
-        self.component_mutable = Bitmap::new();
+        self.resource_immutable = BitSet::new();
+        self.resource_mutable = BitSet::new();
+        self.component_immutable = Signature::new();
+        self.component_mutable = Signature::new();
     }
 }
 
 #[derive(Default, Clone)]
 pub struct AccessBuilder {
-    resource_immutable: Bitmap,
-    resource_mutable: Bitmap,
+    resource_immutable: BitSet,
+    resource_mutable: BitSet,
     component: Vec<FilteredComponentAccess>,
 }
 
@@ -75,7 +75,7 @@ impl AccessBuilder {
     }
 
     pub fn add_resource_immutable(&mut self, index: usize) -> Result<(), Conflict> {
-        if !(self.resource_mutable & Bitmap::new().with_set(index)).is_zero() {
+        if self.resource_mutable.get(index) {
             return Err(Conflict::ResMutImmut);
         }
         self.resource_immutable.set(index);
@@ -83,11 +83,10 @@ impl AccessBuilder {
     }
 
     pub fn add_resource_mutable(&mut self, index: usize) -> Result<(), Conflict> {
-        let access = Bitmap::new().with_set(index);
-        if !(self.resource_mutable & access).is_zero() {
+        if self.resource_mutable.get(index) {
             return Err(Conflict::ResDuplicateMut);
         }
-        if !(self.resource_immutable & access).is_zero() {
+        if self.resource_immutable.get(index) {
             return Err(Conflict::ResMutImmut);
         }
         self.resource_mutable.set(index);
@@ -105,15 +104,15 @@ impl AccessBuilder {
     }
 
     pub fn build(self) -> Access {
-        let mut component_immutable = Bitmap::new();
-        let mut component_mutable = Bitmap::new();
+        let mut component_immutable = Signature::new();
+        let mut component_mutable = Signature::new();
 
         for access in self.component {
-            component_immutable |= access.immutable;
-            component_mutable |= access.mutable;
+            component_immutable |= &access.immutable;
+            component_mutable |= &access.mutable;
         }
 
-        component_immutable &= !component_mutable;
+        component_immutable.difference_with(&component_mutable);
 
         Access {
             resource_immutable: self.resource_immutable,
@@ -124,32 +123,33 @@ impl AccessBuilder {
     }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Default)]
 pub struct FilteredComponentAccess {
-    immutable: Bitmap,
-    mutable: Bitmap,
-    with: Bitmap,
-    without: Bitmap,
+    immutable: Signature,
+    mutable: Signature,
+    with: Signature,
+    without: Signature,
+    include_disabled: bool,
 }
 
 impl FilteredComponentAccess {
     #[inline]
     pub fn is_disjoint(&self, other: &Self) -> bool {
-        !((self.with & other.without).is_zero() && (other.with & other.without).is_zero())
+        !(self.with.is_disjoint(&other.without) && other.with.is_disjoint(&other.without))
     }
 
     #[inline]
     pub fn intersects(&self, other: &Self) -> bool {
-        (self.with & other.without).is_zero() && (other.with & other.without).is_zero()
+        self.with.is_disjoint(&other.without) && other.with.is_disjoint(&other.without)
     }
 
     #[inline]
     pub fn conflicts(&self, other: &Self) -> bool {
         self.intersects(other)
         && !(
-            (self.immutable & other.mutable).is_zero() &&
-            (other.immutable & self.mutable).is_zero() &&
-            (self.mutable & other.mutable).is_zero()
+            self.immutable.is_disjoint(&other.mutable) &&
+            other.immutable.is_disjoint(&self.mutable) &&
+            self.mutable.is_disjoint(&other.mutable)
         )
     }
 
@@ -157,17 +157,17 @@ impl FilteredComponentAccess {
         if self.is_disjoint(other) {
             return None;
         }
-        if !((self.immutable & other.mutable).is_zero() && (other.immutable & self.mutable).is_zero()) {
+        if self.immutable.intersects(&other.mutable) || other.immutable.intersects(&self.mutable) {
             return Some(Conflict::CompMutImmut);
         }
-        if !(self.mutable & other.mutable).is_zero() {
+        if self.mutable.intersects(&other.mutable) {
             return Some(Conflict::CompDuplicateMut);
         }
         None
     }
 
     pub fn add_immutable(&mut self, index: usize) -> Result<(), Conflict> {
-        if !(self.mutable & Bitmap::new().with_set(index)).is_zero() {
+        if self.mutable.get(index) {
             return Err(Conflict::CompMutImmut);
         }
         self.immutable.set(index);
@@ -175,11 +175,10 @@ impl FilteredComponentAccess {
     }
 
     pub fn add_mutable(&mut self, index: usize) -> Result<(), Conflict> {
-        let bitmap = Bitmap::new().with_set(index);
-        if !(self.immutable & bitmap).is_zero() {
+        if self.immutable.get(index) {
             return Err(Conflict::CompMutImmut);
         }
-        if !(self.mutable & bitmap).is_zero() {
+        if self.mutable.get(index) {
             return Err(Conflict::CompDuplicateMut);
         }
         self.mutable.set(index);
@@ -187,17 +186,16 @@ impl FilteredComponentAccess {
     }
 
     pub fn add_with(&mut self, index: usize) -> Result<(), Conflict> {
-        let bitmap = Bitmap::new().with_set(index);
-        if !(self.without & bitmap).is_zero() {
+        if self.without.get(index) {
             return Err(Conflict::CompEmptySet);
         }
         self.with.set(index);
         Ok(())
     }
 
-    pub fn join_with(&mut self, bitmap: Bitmap) -> Result<(), Conflict> {
-        let sum = self.with | bitmap;
-        if !(self.without & sum).is_zero() {
+    pub fn join_with(&mut self, signature: Signature) -> Result<(), Conflict> {
+        let sum = &self.with | &signature;
+        if self.without.intersects(&sum) {
             return Err(Conflict::CompEmptySet);
         }
         self.with = sum;
@@ -205,38 +203,47 @@ impl FilteredComponentAccess {
     }
 
     pub fn add_without(&mut self, index: usize) -> Result<(), Conflict> {
-        let bitmap = Bitmap::new().with_set(index);
-        if !((self.with & bitmap).is_zero() && ((self.immutable | self.mutable) & bitmap).is_zero()) {
+        if self.with.get(index) || self.immutable.get(index) || self.mutable.get(index) {
             return Err(Conflict::CompEmptySet);
         }
         self.without.set(index);
         Ok(())
     }
 
-    pub fn join_without(&mut self, bitmap: Bitmap) -> Result<(), Conflict> {
-        let sum = self.without | bitmap;
-        if !((self.with & sum).is_zero() && ((self.immutable | self.mutable) & sum).is_zero()) {
+    pub fn join_without(&mut self, signature: Signature) -> Result<(), Conflict> {
+        let sum = &self.without | &signature;
+        if self.with.intersects(&sum) || self.immutable.intersects(&sum) || self.mutable.intersects(&sum) {
             return Err(Conflict::CompEmptySet);
         }
         self.without = sum;
         Ok(())
     }
 
-    pub fn immutable(&self) -> &Bitmap {
+    pub fn immutable(&self) -> &Signature {
         &self.immutable
     }
 
-    pub fn mutable(&self) -> &Bitmap {
+    pub fn mutable(&self) -> &Signature {
         &self.mutable
     }
 
-    pub fn with(&self) -> &Bitmap {
+    pub fn with(&self) -> &Signature {
         &self.with
     }
 
-    pub fn without(&self) -> &Bitmap {
+    pub fn without(&self) -> &Signature {
         &self.without
     }
+
+    #[inline]
+    pub fn set_include_disabled(&mut self) {
+        self.include_disabled = true;
+    }
+
+    #[inline]
+    pub const fn include_disabled(&self) -> bool {
+        self.include_disabled
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
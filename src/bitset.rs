@@ -0,0 +1,187 @@
+use std::{fmt::{Debug, Display}, ops::{BitAnd, BitOr, BitOrAssign}};
+
+type Block = u128;
+const BLOCK_WIDTH: usize = Block::BITS as usize;
+
+/// Growable bitset, the first 128 bits are stored inline and never allocate
+#[derive(Default, Clone, Hash, PartialEq, Eq)]
+pub struct BitSet {
+    inline: Block,
+    // bits from BLOCK_WIDTH onwards, trailing zero blocks are always trimmed so Eq and Hash stay structural
+    spilled: Vec<Block>,
+}
+
+impl BitSet {
+    #[inline]
+    pub const fn new() -> Self {
+        Self { inline: 0, spilled: Vec::new() }
+    }
+
+    #[inline]
+    pub fn with_set(mut self, index: usize) -> Self {
+        self.set(index);
+        self
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> bool {
+        (self.block(index / BLOCK_WIDTH) & Self::mask(index)) > 0
+    }
+
+    #[inline]
+    pub fn set(&mut self, index: usize) {
+        let block = index / BLOCK_WIDTH;
+        if block == 0 {
+            self.inline |= Self::mask(index);
+            return;
+        }
+        if self.spilled.len() < block {
+            self.spilled.resize(block, 0);
+        }
+        self.spilled[block - 1] |= Self::mask(index);
+    }
+
+    #[inline]
+    pub fn unset(&mut self, index: usize) {
+        let block = index / BLOCK_WIDTH;
+        if block == 0 {
+            self.inline &= !Self::mask(index);
+            return;
+        }
+        if let Some(bits) = self.spilled.get_mut(block - 1) {
+            *bits &= !Self::mask(index);
+            self.trim();
+        }
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.inline == 0 && self.spilled.is_empty()
+    }
+
+    pub fn ones(&self) -> u32 {
+        self.blocks().map(Block::count_ones).sum()
+    }
+
+    /// Every bit of `self` is also set in `other`
+    #[inline]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.spilled.len() <= other.spilled.len()
+        && self.blocks().zip(other.blocks()).all(|(a, b)| a & !b == 0)
+    }
+
+    /// At least one bit is set in both
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.blocks().zip(other.blocks()).any(|(a, b)| a & b != 0)
+    }
+
+    #[inline]
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        !self.intersects(other)
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        self.inline |= other.inline;
+        if self.spilled.len() < other.spilled.len() {
+            self.spilled.resize(other.spilled.len(), 0);
+        }
+        for (a, b) in self.spilled.iter_mut().zip(other.spilled.iter()) {
+            *a |= b;
+        }
+    }
+
+    fn intersect_with(&mut self, other: &Self) {
+        self.inline &= other.inline;
+        self.spilled.truncate(other.spilled.len());
+        for (a, b) in self.spilled.iter_mut().zip(other.spilled.iter()) {
+            *a &= b;
+        }
+        self.trim();
+    }
+
+    /// Unsets every bit that is set in `other`
+    pub fn difference_with(&mut self, other: &Self) {
+        self.inline &= !other.inline;
+        for (a, b) in self.spilled.iter_mut().zip(other.spilled.iter()) {
+            *a &= !b;
+        }
+        self.trim();
+    }
+
+    /// Indices of the set bits in ascending order
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks().enumerate().flat_map(|(block, mut bits)| {
+            std::iter::from_fn(move || {
+                if bits == 0 { return None; }
+                let index = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(block * BLOCK_WIDTH + index)
+            })
+        })
+    }
+
+    #[inline]
+    fn blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        std::iter::once(self.inline).chain(self.spilled.iter().copied())
+    }
+
+    #[inline]
+    fn block(&self, block: usize) -> Block {
+        match block {
+            0 => self.inline,
+            _ => self.spilled.get(block - 1).copied().unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    const fn mask(index: usize) -> Block {
+        1 << (index % BLOCK_WIDTH)
+    }
+
+    fn trim(&mut self) {
+        while self.spilled.last() == Some(&0) {
+            self.spilled.pop();
+        }
+    }
+}
+
+impl Debug for BitSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter_ones()).finish()
+    }
+}
+
+impl Display for BitSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for bits in self.spilled.iter().rev() {
+            f.write_fmt(format_args!("{:0>128b}", bits))?;
+        }
+        let width = if self.spilled.is_empty() { 32 } else { BLOCK_WIDTH };
+        f.write_fmt(format_args!("{:0>width$b}", self.inline))
+    }
+}
+
+impl BitAnd for &BitSet {
+    type Output = BitSet;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut out = self.clone();
+        out.intersect_with(rhs);
+        out
+    }
+}
+
+impl BitOr for &BitSet {
+    type Output = BitSet;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut out = self.clone();
+        out.union_with(rhs);
+        out
+    }
+}
+
+impl BitOrAssign<&BitSet> for BitSet {
+    fn bitor_assign(&mut self, rhs: &BitSet) {
+        self.union_with(rhs);
+    }
+}
@@ -0,0 +1,209 @@
+use std::{fmt::Debug, ops::{Deref, DerefMut}};
+
+/// Number of ticks between two clamping passes over the stored ticks, see [`Tick::check_tick`]
+pub const CHECK_TICK_THRESHOLD: u32 = 518_400_000;
+/// Oldest age a stored tick can have before it is clamped, leaving room for a whole threshold to pass until the next check
+pub const MAX_CHANGE_AGE: u32 = u32::MAX - (2 * CHECK_TICK_THRESHOLD - 1);
+
+/// Point in time of the world, advanced every time a system runs
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Tick(u32);
+
+impl Tick {
+    #[inline]
+    pub const fn new(tick: u32) -> Self {
+        Self(tick)
+    }
+
+    #[inline]
+    pub const fn get(&self) -> u32 {
+        self.0
+    }
+
+    /// Whether the tick happened after `last_run`, as seen from `this_run`, tolerates the counter wrapping around
+    #[inline]
+    pub const fn is_newer_than(&self, last_run: Tick, this_run: Tick) -> bool {
+        this_run.0.wrapping_sub(last_run.0) > this_run.0.wrapping_sub(self.0)
+    }
+
+    /// Clamps the tick to [`MAX_CHANGE_AGE`] behind `change_tick`, so that the wrapping counter never makes it look new again
+    #[inline]
+    pub fn check_tick(&mut self, change_tick: Tick) {
+        if change_tick.0.wrapping_sub(self.0) > MAX_CHANGE_AGE {
+            self.0 = change_tick.0.wrapping_sub(MAX_CHANGE_AGE);
+        }
+    }
+
+    /// Whether enough ticks passed since `last_check` for stored ticks to need clamping
+    #[inline]
+    pub(crate) const fn needs_check(&self, last_check: Tick) -> bool {
+        self.0.wrapping_sub(last_check.0) >= CHECK_TICK_THRESHOLD
+    }
+}
+
+/// When a component was added to its entity and last written to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ComponentTicks {
+    pub added: Tick,
+    pub changed: Tick,
+}
+
+impl ComponentTicks {
+    #[inline]
+    pub const fn new(tick: Tick) -> Self {
+        Self { added: tick, changed: tick }
+    }
+
+    #[inline]
+    pub fn check_ticks(&mut self, change_tick: Tick) {
+        self.added.check_tick(change_tick);
+        self.changed.check_tick(change_tick);
+    }
+}
+
+/// Last and current run of the system a query is fetched for
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct SystemTicks {
+    last_run: Tick,
+    this_run: Tick,
+}
+
+impl SystemTicks {
+    #[inline]
+    pub const fn new(last_run: Tick, this_run: Tick) -> Self {
+        Self { last_run, this_run }
+    }
+
+    #[inline]
+    pub const fn last_run(&self) -> Tick {
+        self.last_run
+    }
+
+    #[inline]
+    pub const fn this_run(&self) -> Tick {
+        self.this_run
+    }
+
+    #[inline]
+    pub const fn is_added(&self, ticks: &ComponentTicks) -> bool {
+        ticks.added.is_newer_than(self.last_run, self.this_run)
+    }
+
+    #[inline]
+    pub const fn is_changed(&self, ticks: &ComponentTicks) -> bool {
+        ticks.changed.is_newer_than(self.last_run, self.this_run)
+    }
+}
+
+/// Shared access to a component that knows whether it was added or changed since the system last ran
+pub struct Ref<'a, C> {
+    value: &'a C,
+    ticks: &'a ComponentTicks,
+    system_ticks: SystemTicks,
+}
+
+impl<'a, C> Ref<'a, C> {
+    #[inline]
+    pub(crate) const fn new(value: &'a C, ticks: &'a ComponentTicks, system_ticks: SystemTicks) -> Self {
+        Self { value, ticks, system_ticks }
+    }
+
+    #[inline]
+    pub const fn is_added(&self) -> bool {
+        self.system_ticks.is_added(self.ticks)
+    }
+
+    #[inline]
+    pub const fn is_changed(&self) -> bool {
+        self.system_ticks.is_changed(self.ticks)
+    }
+
+    #[inline]
+    pub const fn ticks(&self) -> ComponentTicks {
+        *self.ticks
+    }
+
+    #[inline]
+    pub const fn into_inner(self) -> &'a C {
+        self.value
+    }
+}
+
+impl<C> Deref for Ref<'_, C> {
+    type Target = C;
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<C: Debug> Debug for Ref<'_, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// Unique access to a component, marks it changed only when dereferenced mutably
+pub struct Mut<'a, C> {
+    value: &'a mut C,
+    ticks: &'a mut ComponentTicks,
+    system_ticks: SystemTicks,
+}
+
+impl<'a, C> Mut<'a, C> {
+    #[inline]
+    pub(crate) const fn new(value: &'a mut C, ticks: &'a mut ComponentTicks, system_ticks: SystemTicks) -> Self {
+        Self { value, ticks, system_ticks }
+    }
+
+    #[inline]
+    pub const fn is_added(&self) -> bool {
+        self.system_ticks.is_added(self.ticks)
+    }
+
+    #[inline]
+    pub const fn is_changed(&self) -> bool {
+        self.system_ticks.is_changed(self.ticks)
+    }
+
+    #[inline]
+    pub const fn ticks(&self) -> ComponentTicks {
+        *self.ticks
+    }
+
+    #[inline]
+    pub const fn set_changed(&mut self) {
+        self.ticks.changed = self.system_ticks.this_run;
+    }
+
+    /// Mutable access that doesn't mark the component changed
+    #[inline]
+    pub const fn bypass_change_detection(&mut self) -> &mut C {
+        self.value
+    }
+
+    #[inline]
+    pub const fn into_inner(mut self) -> &'a mut C {
+        self.set_changed();
+        self.value
+    }
+}
+
+impl<C> Deref for Mut<'_, C> {
+    type Target = C;
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<C> DerefMut for Mut<'_, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.set_changed();
+        self.value
+    }
+}
+
+impl<C: Debug> Debug for Mut<'_, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
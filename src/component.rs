@@ -1,28 +1,327 @@
-use std::{any::TypeId, collections::{hash_map::Entry, HashMap}, ops::BitOrAssign};
+use std::{alloc::Layout, any::TypeId, borrow::Cow, collections::{hash_map::Entry, HashMap}, ptr::NonNull};
 
-use crate::{Commands, Entity, World, bitmap::Bitmap, storage::{ptr::PtrMut, sparse_set::{SparseSet, blob_sparse_set::BlobSparseSet}}};
+use crate::{Commands, Entity, Name, World, bitset::BitSet, group::{Edges, Groups, Transitions}, index::{ComponentIndex, ErasedComponentIndex, IndexableComponent}, change_detection::{ComponentTicks, Tick}, entity::map::EntityMap, observer::Observers, trigger::{OnAdd, OnInsert, OnRemove}, storage::{ptr::{Ptr, PtrMut}, sparse_set::{SparseSet, blob_sparse_set::BlobSparseSet}, table::{Column, Table}}};
 
 #[allow(unused)]
 pub trait Component: Send + Sync + 'static + Sized {
+    /// [`Immutable`] components are never handed out mutably, they can only be replaced
+    type Mutability: ComponentMutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
     fn on_add(&mut self, commands: &mut Commands) {}
     fn on_remove(&mut self, commands: &mut Commands) {}
+    /// Sets the [`ComponentHooks`] of the component, runs once when the component is registered
+    fn register_component_hooks(hooks: &mut ComponentHooks) {}
+    /// Lists the components inserted with their default value alongside this one, runs once when the component is registered
+    fn register_required_components(required: &mut RequiredComponents) {}
 }
 
-pub type Signature = Bitmap;
+/// Where the values of a component are kept, chosen with `#[component(storage = "SparseSet")]` on the derive.
+/// Unknown keys are rejected:
+/// ```compile_fail
+/// # use ecs::*;
+/// #[derive(Component)]
+/// #[component(storge = "SparseSet")]
+/// struct Highlighted;
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum StorageType {
+    /// Contiguous columns shared by the entities with the same table components, fastest to iterate
+    #[default]
+    Table,
+    /// A sparse set of the component alone, adding and removing it doesn't move the entity's other values
+    SparseSet,
+}
+
+pub trait ComponentMutability: Send + Sync + 'static {
+    const MUTABLE: bool;
+}
+
+/// Components that can be mutated in place, the default
+pub struct Mutable;
+impl ComponentMutability for Mutable {
+    const MUTABLE: bool = true;
+}
+
+/// Components that can only be replaced through `set_component` or `Commands`, so every change runs their replace hooks
+///
+/// Mutable access to them doesn't compile:
+/// ```compile_fail
+/// # use ecs::*;
+/// #[derive(Component)]
+/// #[component(immutable)]
+/// struct Team(u32);
+/// fn system(query: Query<&mut Team>) {}
+/// Schedule::default().add_system(system);
+/// ```
+/// ```compile_fail
+/// # use ecs::*;
+/// #[derive(Component)]
+/// #[component(immutable)]
+/// struct Team(u32);
+/// fn system(query: Query<Mut<Team>>) {}
+/// Schedule::default().add_system(system);
+/// ```
+/// ```compile_fail
+/// # use ecs::*;
+/// #[derive(Component)]
+/// #[component(immutable)]
+/// struct Team(u32);
+/// let mut world = World::default();
+/// let entity = world.spawn(Team(0));
+/// world.get_component_mut::<Team>(entity);
+/// ```
+pub struct Immutable;
+impl ComponentMutability for Immutable {
+    const MUTABLE: bool = false;
+}
+
+/// Components that can be accessed through `&mut C`
+pub trait MutableComponent: Component<Mutability = Mutable> {}
+impl<C: Component<Mutability = Mutable>> MutableComponent for C {}
+
+/// Marks an entity as disabled, queries skip disabled entities unless they use [`crate::IncludeDisabled`] or require `Disabled` explicitly
+pub struct Disabled;
+impl Component for Disabled {}
+
+pub type Signature = BitSet;
+
+/// Entity and component a [`ComponentHook`] runs for
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HookContext {
+    pub entity: Entity,
+    pub component_id: ComponentId,
+}
+
+pub type ComponentHook = fn(&mut Commands, HookContext);
+
+/// Hooks of a single component type, set through [`Component::register_component_hooks`] or [`World::register_component_hooks`]
+#[derive(Clone, Copy, Default)]
+pub struct ComponentHooks {
+    on_add: Option<ComponentHook>,
+    on_insert: Option<ComponentHook>,
+    on_replace: Option<ComponentHook>,
+    on_remove: Option<ComponentHook>,
+    on_despawn: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    /// Runs when the component is added to an entity that didn't have it
+    pub fn on_add(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_add = Some(hook);
+        self
+    }
+
+    /// Runs every time the component is written, after `on_add`
+    pub fn on_insert(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_insert = Some(hook);
+        self
+    }
+
+    /// Runs before the value of the component is overwritten or removed
+    pub fn on_replace(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_replace = Some(hook);
+        self
+    }
+
+    /// Runs when the component is removed from an entity, including on despawn
+    pub fn on_remove(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_remove = Some(hook);
+        self
+    }
+
+    /// Runs when an entity with the component is despawned, before `on_replace` and `on_remove`
+    pub fn on_despawn(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_despawn = Some(hook);
+        self
+    }
+
+    #[inline]
+    pub(crate) fn run_add(&self, commands: &mut Commands, context: HookContext) {
+        if let Some(hook) = self.on_add { hook(commands, context) }
+    }
+
+    #[inline]
+    pub(crate) fn run_insert(&self, commands: &mut Commands, context: HookContext) {
+        if let Some(hook) = self.on_insert { hook(commands, context) }
+    }
+
+    #[inline]
+    pub(crate) fn run_replace(&self, commands: &mut Commands, context: HookContext) {
+        if let Some(hook) = self.on_replace { hook(commands, context) }
+    }
+
+    #[inline]
+    pub(crate) fn run_remove(&self, commands: &mut Commands, context: HookContext) {
+        if let Some(hook) = self.on_remove { hook(commands, context) }
+    }
+
+    #[inline]
+    pub(crate) fn run_despawn(&self, commands: &mut Commands, context: HookContext) {
+        if let Some(hook) = self.on_despawn { hook(commands, context) }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum LifecycleEvent {
+    Add,
+    Insert,
+    Remove,
+}
+
+// lifecycle trigger types of a component, `observed` is cached for a single observers generation
+// dynamic components have no trigger types and are never observed
+#[derive(Clone, Copy)]
+struct ComponentLifecycle {
+    event_type_ids: Option<[TypeId; 3]>,
+    observed: [bool; 3],
+    generation: usize,
+    triggers: [fn(&mut World, Entity); 3],
+}
+
+impl ComponentLifecycle {
+    fn new<C: Component>() -> Self {
+        Self {
+            event_type_ids: Some([TypeId::of::<OnAdd<C>>(), TypeId::of::<OnInsert<C>>(), TypeId::of::<OnRemove<C>>()]),
+            observed: [false; 3],
+            generation: usize::MAX,
+            triggers: [
+                |world, entity| world.trigger_lifecycle(OnAdd::<C>::new(), entity),
+                |world, entity| world.trigger_lifecycle(OnInsert::<C>::new(), entity),
+                |world, entity| world.trigger_lifecycle(OnRemove::<C>::new(), entity),
+            ],
+        }
+    }
+
+    const fn dynamic() -> Self {
+        Self {
+            event_type_ids: None,
+            observed: [false; 3],
+            generation: usize::MAX,
+            triggers: [|_, _| {}; 3],
+        }
+    }
+}
+
+/// Writes a copy of the value behind the first pointer into the uninitialized second one
+pub type RawCloneFn = unsafe fn(Ptr<'_>, PtrMut<'_>);
+
+/// Describes a component without a Rust type, registered through [`World::register_dynamic_component`]
+#[derive(Clone, Debug)]
+pub struct ComponentDescriptor {
+    pub name: Cow<'static, str>,
+    pub layout: Layout,
+    /// Drops the value in place, `None` when the value needs no cleanup
+    pub drop_fn: Option<unsafe fn(PtrMut<'_>)>,
+    /// Writes a copy of the first value into the uninitialized second one, lets [`World::clone_entity`] copy the component
+    pub clone_fn: Option<RawCloneFn>,
+    pub storage_type: StorageType,
+}
+
+impl ComponentDescriptor {
+    pub fn new(name: impl Into<Cow<'static, str>>, layout: Layout) -> Self {
+        Self { name: name.into(), layout, drop_fn: None, clone_fn: None, storage_type: StorageType::Table }
+    }
+
+    pub fn with_drop(mut self, drop_fn: unsafe fn(PtrMut<'_>)) -> Self {
+        self.drop_fn = Some(drop_fn);
+        self
+    }
+
+    pub fn with_clone(mut self, clone_fn: RawCloneFn) -> Self {
+        self.clone_fn = Some(clone_fn);
+        self
+    }
+
+    pub fn with_storage_type(mut self, storage_type: StorageType) -> Self {
+        self.storage_type = storage_type;
+        self
+    }
+}
+
+/// Components that get inserted with their default value whenever the component requiring them is, unless the entity already has them
+#[derive(Default)]
+pub struct RequiredComponents(Vec<fn(&mut Components) -> RequiredComponent>);
+
+impl RequiredComponents {
+    pub fn require<R: Component + Default>(&mut self) -> &mut Self {
+        self.0.push(RequiredComponent::register::<R>);
+        self
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct RequiredComponent {
+    id: ComponentId,
+    // entity must be alive and spawned under a signature containing the component
+    write: unsafe fn(&mut World, Entity),
+    insert: fn(&mut World, Entity),
+    trigger_added: fn(&mut World, Entity),
+}
+
+impl RequiredComponent {
+    fn register<R: Component + Default>(components: &mut Components) -> Self {
+        Self {
+            id: components.register_component::<R>(),
+            write: |world, entity| unsafe { world.write_spawned_component(entity, R::default()) },
+            insert: |world, entity| world.set_component(entity, R::default()),
+            trigger_added: |world, entity| world.trigger_added::<R>(entity),
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    /// # Safety
+    /// Entity must be alive and spawned under a signature containing the component
+    #[inline]
+    pub(crate) unsafe fn write(&self, world: &mut World, entity: Entity) {
+        unsafe { (self.write)(world, entity) }
+    }
+
+    #[inline]
+    pub(crate) fn insert(&self, world: &mut World, entity: Entity) {
+        (self.insert)(world, entity)
+    }
+
+    #[inline]
+    pub(crate) fn trigger_added(&self, world: &mut World, entity: Entity) {
+        (self.trigger_added)(world, entity)
+    }
+}
+
+// copies the component from the first entity onto the second
+pub(crate) type ComponentCloneFn = fn(&mut World, ComponentId, Entity, Entity, &EntityMap);
 
-pub const MAX_COMPONENTS: usize = Bitmap::WIDTH;
+// value level callbacks of Component, no-ops for dynamic components
+type ComponentCallback = for<'a> fn(PtrMut<'a>, &'a mut Commands);
+
+// runs the remove hooks around dropping a value handed out by Table::swap_remove
+// takes the component's callbacks by value, the table it comes from is still borrowed
+unsafe fn drop_removed(value: PtrMut<'_>, context: HookContext, hooks: ComponentHooks, on_remove: ComponentCallback, drop: unsafe fn(PtrMut<'_>), commands: &mut Commands) {
+    hooks.run_replace(commands, context);
+    on_remove(value, commands);
+    unsafe { drop(value) };
+    hooks.run_remove(commands, context);
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct ComponentId(usize);
 
 impl ComponentId {
+    #[inline]
+    pub(crate) const fn new(id: usize) -> Self {
+        Self(id)
+    }
+
     #[inline]
     pub const fn get(&self) -> usize {
         self.0
     }
 
     #[inline]
-    pub(crate) const fn as_signature(&self) -> Signature {
+    pub(crate) fn as_signature(&self) -> Signature {
         Signature::new().with_set(self.0)
     }
 }
@@ -35,36 +334,122 @@ pub struct ComponentRecord {
 #[derive(Default)]
 pub(crate) struct Components {
     component_records: HashMap<TypeId, ComponentRecord>,
+    // values of sparse set components, table components only use theirs for the layout and drop of their columns
     components: Vec<BlobSparseSet>,
-    component_on_remove: Vec<for<'a> fn(PtrMut<'a>, &'a mut Commands)>,
-    groups: HashMap<Signature, SparseSet<Entity>>,
-    entity_signatures: SparseSet<Signature>,
+    component_storage: Vec<StorageType>,
+    component_names: Vec<Cow<'static, str>>,
+    component_mutable: Vec<bool>,
+    component_index: Vec<Option<ErasedComponentIndex>>,
+    component_on_add: Vec<ComponentCallback>,
+    component_on_remove: Vec<ComponentCallback>,
+    component_clone: Vec<Option<ComponentCloneFn>>,
+    // clone functions of dynamic components, used by their ComponentCloneFn
+    component_raw_clone: Vec<Option<RawCloneFn>>,
+    component_hooks: Vec<ComponentHooks>,
+    component_lifecycle: Vec<ComponentLifecycle>,
+    // transitive requirements of every component, without duplicates
+    component_required: Vec<Box<[RequiredComponent]>>,
+    // components whose requirements are being resolved, a requirement on any of them is a cycle
+    resolving_required: Vec<ComponentId>,
+    groups: Groups,
+    // group of each entity, its signature
+    entity_groups: SparseSet<usize>,
+    tables: Vec<Table>,
+    table_ids: HashMap<Signature, usize>,
+    // table holding the values of each entity's table components
+    entity_tables: SparseSet<usize>,
+    // components stored in tables, masks an entity's signature down to its table's
+    table_components: Signature,
     component_len: usize,
 }
 
 impl Components {
     /// Register component if absent
     pub(crate) fn register_component<C: Component>(&mut self) -> ComponentId {
-        match self.component_records.entry(TypeId::of::<C>()) {
+        let id = match self.component_records.entry(TypeId::of::<C>()) {
             Entry::Vacant(vacant) => {
-                let id = ComponentId(self.component_len);
-                let signature = Bitmap::new().with_set(self.component_len);
+                let signature = Signature::new().with_set(self.component_len);
                 vacant.insert(ComponentRecord {
                     signature,
-                    id,
-                });
-                self.components.push(BlobSparseSet::new::<C>());
-                self.component_on_remove.push(|mut ptr, commands| {
-                    unsafe { ptr.cast_mut::<C>().on_remove(commands) };
+                    id: ComponentId(self.component_len),
                 });
-                assert!(self.component_len <= MAX_COMPONENTS, "component overflow");
-                self.component_len += 1;
-                id
+                let mut hooks = ComponentHooks::default();
+                C::register_component_hooks(&mut hooks);
+                self.push_component(
+                    BlobSparseSet::new::<C>(),
+                    C::STORAGE_TYPE,
+                    Cow::Borrowed(std::any::type_name::<C>()),
+                    C::Mutability::MUTABLE,
+                    |mut ptr, commands| unsafe { ptr.cast_mut::<C>().on_add(commands) },
+                    |mut ptr, commands| unsafe { ptr.cast_mut::<C>().on_remove(commands) },
+                    hooks,
+                    ComponentLifecycle::new::<C>(),
+                )
             },
             Entry::Occupied(occupied) => {
-                occupied.get().id
+                return occupied.get().id;
             },
+        };
+        // names are always indexed, World::entities_named looks them up
+        if TypeId::of::<C>() == TypeId::of::<Name>() {
+            self.component_index[id.0] = Some(ErasedComponentIndex::new::<Name>());
+        }
+        self.register_required_components::<C>(id);
+        id
+    }
+
+    /// Registers a component that has no Rust type, every call registers a new one
+    pub(crate) fn register_dynamic_component(&mut self, descriptor: ComponentDescriptor) -> ComponentId {
+        let storage = BlobSparseSet::from_layout(descriptor.layout, descriptor.drop_fn.unwrap_or(|_| {}));
+        let id = self.push_component(storage, descriptor.storage_type, descriptor.name, true, |_, _| {}, |_, _| {}, ComponentHooks::default(), ComponentLifecycle::dynamic());
+        if let Some(clone_fn) = descriptor.clone_fn {
+            self.component_raw_clone[id.0] = Some(clone_fn);
+            self.component_clone[id.0] = Some(|world, component_id, source, target, _| world.clone_component_by_id(component_id, source, target));
+        }
+        id
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push_component(&mut self, storage: BlobSparseSet, storage_type: StorageType, name: Cow<'static, str>, mutable: bool, on_add: ComponentCallback, on_remove: ComponentCallback, hooks: ComponentHooks, lifecycle: ComponentLifecycle) -> ComponentId {
+        let id = ComponentId(self.component_len);
+        self.components.push(storage);
+        self.component_storage.push(storage_type);
+        self.component_names.push(name);
+        self.component_mutable.push(mutable);
+        self.component_index.push(None);
+        self.component_on_add.push(on_add);
+        self.component_on_remove.push(on_remove);
+        self.component_clone.push(None);
+        self.component_raw_clone.push(None);
+        self.component_hooks.push(hooks);
+        self.component_lifecycle.push(lifecycle);
+        self.component_required.push(Box::default());
+        if storage_type == StorageType::Table {
+            self.table_components.set(id.0);
         }
+        self.component_len += 1;
+        id
+    }
+
+    // resolves the requirements of a freshly registered component, registering them along the way
+    fn register_required_components<C: Component>(&mut self, component_id: ComponentId) {
+        let mut required_components = RequiredComponents::default();
+        C::register_required_components(&mut required_components);
+        if required_components.0.is_empty() { return; }
+        self.resolving_required.push(component_id);
+        let mut resolved: Vec<RequiredComponent> = Vec::new();
+        for register in required_components.0 {
+            let required = register(self);
+            assert!(!self.resolving_required.contains(&required.id), "required components of '{}' form a cycle", std::any::type_name::<C>());
+            let transitive = self.component_required[required.id.0].iter().copied();
+            for required in std::iter::once(required).chain(transitive) {
+                if !resolved.iter().any(|resolved| resolved.id == required.id) {
+                    resolved.push(required);
+                }
+            }
+        }
+        self.resolving_required.pop();
+        self.component_required[component_id.0] = resolved.into_boxed_slice();
     }
 
     /// Entity must be alive
@@ -74,99 +459,423 @@ impl Components {
     }
 
     /// Entity must be alive
-    pub(crate) fn get_mut_component<C: Component>(&mut self, entity: Entity) -> Option<&mut C> {
+    pub(crate) fn get_mut_component<C: Component>(&mut self, entity: Entity, tick: Tick) -> Option<&mut C> {
         let component_id = self.get_component_id::<C>()?;
-        unsafe { self.get_mut_component_by_id::<C>(entity, component_id) }
+        unsafe { self.get_mut_component_by_id::<C>(entity, component_id, tick) }
     }
 
     /// Entity must be alive
-    pub(crate) fn set_component<C: Component>(&mut self, entity: Entity, component: C) -> Option<C> {
-        let components_num = self.component_records.len();
-        let component_record = self.component_records
-            .entry(TypeId::of::<C>())
-            .or_insert_with(|| {
-                self.components.push(BlobSparseSet::new::<C>());
-                self.component_len += 1;
-                assert!(self.component_len <= MAX_COMPONENTS, "component overflow");
-                ComponentRecord {
-                    signature: Bitmap::new().with_set(components_num),
-                    id: ComponentId(components_num),
-                }
-            });
-        let entity_signature = self.entity_signatures.get_mut(entity.id() as usize).expect("ComponentManager set_component entity signature missing");
+    pub(crate) fn set_component<C: Component>(&mut self, entity: Entity, component: C, tick: Tick) -> Option<C> {
+        self.register_component::<C>();
+        let component_id = self.component_records[&TypeId::of::<C>()].id;
+        self.index_remove(entity, component_id);
+        self.group_with(entity, component_id);
+        let replaced = unsafe { self.write_value(entity, component_id, component, tick) };
+        self.index_insert(entity, component_id);
+        replaced
+    }
 
-        let component_signature = component_record.signature;
-        let component_id = component_record.id;
+    /// Entity must be alive
+    pub(crate) fn remove_component<C: Component>(&mut self, entity: Entity) -> Option<C> {
+        let component_id = self.component_records.get(&TypeId::of::<C>())?.id;
+        let group_id = *self.entity_groups.get(entity.id() as usize)?;
+        if !self.groups.signature(group_id).get(component_id.0) {
+            return None;
+        }
+        let target = self.groups.without_component(group_id, component_id.0);
+        self.move_group(entity, group_id, target);
+
+        let component = unsafe { self.take_value::<C>(entity, component_id) }.expect("component manager remove_component id missing");
+        self.remove_from_table(entity, component_id);
+        Some(component)
+    }
+
+    /// Moves the value into the component's storage and groups the entity with it, returns whether a value was replaced, which is then swapped behind ptr
+    /// # Safety
+    /// Entity must be alive
+    /// Value must point to a valid value of the component
+    pub(crate) unsafe fn insert_by_id(&mut self, entity: Entity, component_id: ComponentId, value: PtrMut<'_>, tick: Tick) -> bool {
+        self.index_remove(entity, component_id);
+        self.group_with(entity, component_id);
+        let replaced = unsafe { self.write_ptr(entity, component_id, value, tick) };
+        self.index_insert(entity, component_id);
+        replaced
+    }
+
+    // writes the value wherever the component is stored, returns the replaced value
+    #[inline]
+    unsafe fn write_value<C: Component>(&mut self, entity: Entity, component_id: ComponentId, mut component: C, tick: Tick) -> Option<C> {
+        let value = PtrMut::new(NonNull::from(&mut component).cast());
+        match unsafe { self.write_ptr(entity, component_id, value, tick) } {
+            true => Some(component),
+            false => {
+                std::mem::forget(component);
+                None
+            }
+        }
+    }
 
-        let sparse_set = &mut self.components[component_id.0];
+    // moves the value into the component's storage, a replaced value is swapped behind ptr and returns true
+    // writing a table component the entity's table lacks moves the entity to the table with its column
+    unsafe fn write_ptr(&mut self, entity: Entity, component_id: ComponentId, mut value: PtrMut<'_>, tick: Tick) -> bool {
+        let index = entity.id() as usize;
+        if self.component_storage[component_id.0] == StorageType::SparseSet {
+            return unsafe { self.components[component_id.0].insert_raw(index, value, tick) };
+        }
+        if let Some((table_id, row)) = self.table_location(entity, component_id) {
+            let size = self.components[component_id.0].item_layout().size();
+            let column = self.tables[table_id].column_mut(component_id.0).expect("component manager write_ptr column missing");
+            // a spawned entity already has its row, its columns are pushed one by one
+            if column.len() == row {
+                unsafe { column.push(value, ComponentTicks::new(tick)) };
+                return false;
+            }
+            let (mut stored, ticks) = column.get_mut_with_ticks(row);
+            unsafe { std::ptr::swap_nonoverlapping(stored.as_ptr(), value.as_ptr(), size) };
+            ticks.changed = tick;
+            return true;
+        }
+        let table_id = *self.entity_tables.get(index).expect("component manager write_ptr entity table missing");
+        let target = self.with_component(table_id, component_id.0);
+        self.move_table(entity, table_id, target, None);
+        let column = self.tables[target].column_mut(component_id.0).expect("component manager write_ptr column missing");
+        unsafe { column.push(value, ComponentTicks::new(tick)) };
+        false
+    }
 
-        if (*entity_signature & component_signature).is_zero() {
-            let group = self.groups.get_mut(entity_signature).expect("entity doesnt belong to any groups");
-            group.remove(entity.id() as usize);
-            entity_signature.bitor_assign(component_signature);
-            let new_group = self.groups.entry(*entity_signature).or_default();
-            new_group.insert(entity.id() as usize, entity);
+    // table and row of the entity's value when the component is stored in its table
+    #[inline]
+    fn table_location(&self, entity: Entity, component_id: ComponentId) -> Option<(usize, usize)> {
+        if self.component_storage[component_id.0] != StorageType::Table { return None; }
+        let index = entity.id() as usize;
+        let table_id = *self.entity_tables.get(index)?;
+        let table = &self.tables[table_id];
+        if !table.signature().get(component_id.0) { return None; }
+        Some((table_id, table.row(index)?))
+    }
+
+    fn table_id(&mut self, signature: Signature) -> usize {
+        if let Some(table_id) = self.table_ids.get(&signature) {
+            return *table_id;
         }
-        unsafe { sparse_set.insert(entity.id() as usize, component) }
+        let columns = signature.iter_ones()
+            .map(|index| (index, Column::new(self.components[index].item_layout(), self.components[index].drop_fn())))
+            .collect::<Vec<_>>();
+        let table_id = self.tables.len();
+        self.tables.push(Table::new(signature.clone(), columns));
+        self.table_ids.insert(signature, table_id);
+        table_id
     }
 
+    // moves the entity's values between tables, columns the source lacks must be pushed by the caller
+    // values without a column in the target are dropped running their remove hooks, without commands they were already read out by take_value and are forgotten
+    fn move_table(&mut self, entity: Entity, from: usize, to: usize, mut commands: Option<&mut Commands>) {
+        let index = entity.id() as usize;
+        let [source, target] = self.tables.get_disjoint_mut([from, to]).expect("component manager move_table invalid tables");
+        target.push_entity(entity);
+        source.swap_remove(index, |component_id, value, ticks| match (target.column_mut(component_id), commands.as_deref_mut()) {
+            (Some(column), _) => unsafe { column.push(value, ticks) },
+            (None, Some(commands)) => unsafe {
+                let context = HookContext { entity, component_id: ComponentId(component_id) };
+                drop_removed(value, context, self.component_hooks[component_id], self.component_on_remove[component_id], self.components[component_id].drop_fn(), commands);
+            },
+            (None, None) => {},
+        });
+        *self.entity_tables.get_mut(index).expect("component manager move_table entity table missing") = to;
+    }
+
+    // moves the entity out of the column of a component whose value was read out with take_value
+    fn remove_from_table(&mut self, entity: Entity, component_id: ComponentId) {
+        let Some(table_id) = self.entity_tables.get(entity.id() as usize).copied() else { return; };
+        if !self.tables[table_id].signature().get(component_id.0) { return; }
+        let target = self.without_component(table_id, component_id.0);
+        self.move_table(entity, table_id, target, None);
+    }
+
+    // moves the entity out of the columns of the removed components, see move_table for what happens to their values
+    fn remove_signature_from_table(&mut self, entity: Entity, removed: &Signature, commands: Option<&mut Commands>) {
+        let Some(table_id) = self.entity_tables.get(entity.id() as usize).copied() else { return; };
+        if self.tables[table_id].signature().is_disjoint(removed) { return; }
+        let mut signature = self.tables[table_id].signature().clone();
+        signature.difference_with(removed);
+        let target = self.table_id(signature);
+        self.move_table(entity, table_id, target, commands);
+    }
+
+    /// Reads the component out of the entity's storage, a table's copy is left to be forgotten when the entity leaves the column
+    /// with remove_signature without commands
+    /// # Safety
     /// Entity must be alive
-    pub(crate) fn remove_component<C: Component>(&mut self, entity: Entity) -> Option<C> {
-        let component_record = self.component_records.get(&TypeId::of::<C>())?;
-        let entity_signature = self.entity_signatures.get_mut(entity.id() as usize)?;
+    /// Component_id must correspond to a component array of type C
+    pub(crate) unsafe fn take_value<C: Component>(&mut self, entity: Entity, component_id: ComponentId) -> Option<C> {
+        self.index_remove(entity, component_id);
+        if let Some((table_id, row)) = self.table_location(entity, component_id) {
+            let mut value = self.tables[table_id].column_mut(component_id.0)?.get_mut(row);
+            return Some(unsafe { value.as_ptr().cast::<C>().read() });
+        }
+        unsafe { self.components[component_id.0].remove_as::<C>(entity.id() as usize) }
+    }
 
-        let component_signature = component_record.signature;
-        let component_id = component_record.id;
+    /// Entity must be alive
+    #[inline]
+    pub(crate) fn get_ptr_by_id(&self, entity: Entity, component_id: ComponentId) -> Option<Ptr<'_>> {
+        if let Some((table_id, row)) = self.table_location(entity, component_id) {
+            return self.tables[table_id].column(component_id.0).map(|column| column.get(row));
+        }
+        self.components[component_id.0].get_ptr(entity.id() as usize)
+    }
 
-        let sparse_set = &mut self.components[component_id.0];
-        if (*entity_signature & component_signature).is_zero() {
-            return None;
+    /// Entity must be alive
+    #[inline]
+    pub(crate) fn get_ptr_with_ticks_by_id(&self, entity: Entity, component_id: ComponentId) -> Option<(Ptr<'_>, &ComponentTicks)> {
+        if let Some((table_id, row)) = self.table_location(entity, component_id) {
+            return self.tables[table_id].column(component_id.0).map(|column| (column.get(row), column.get_ticks(row)));
+        }
+        self.components[component_id.0].get_ptr_with_ticks(entity.id() as usize)
+    }
+
+    /// Entity must be alive
+    #[inline]
+    pub(crate) fn get_mut_ptr_with_ticks_by_id(&mut self, entity: Entity, component_id: ComponentId) -> Option<(PtrMut<'_>, &mut ComponentTicks)> {
+        if let Some((table_id, row)) = self.table_location(entity, component_id) {
+            return self.tables[table_id].column_mut(component_id.0).map(|column| column.get_mut_with_ticks(row));
         }
-        let group = self.groups.get_mut(entity_signature).expect("entity doesnt belong to any groups");
-        group.remove(entity.id() as usize);
-        entity_signature.bitor_assign(component_signature);
-        let new_group = self.groups.entry(*entity_signature).or_default();
-        new_group.insert(entity.id() as usize, entity);
+        self.components[component_id.0].get_mut_ptr_with_ticks(entity.id() as usize)
+    }
+
+    /// # Safety
+    /// Value must point to a valid value of the component, which must not be used afterwards
+    #[inline]
+    pub(crate) unsafe fn drop_value(&self, component_id: ComponentId, value: PtrMut<'_>) {
+        unsafe { self.components[component_id.0].drop_value(value) }
+    }
+
+    #[inline]
+    pub(crate) fn on_add(&self, component_id: ComponentId) -> ComponentCallback {
+        self.component_on_add[component_id.0]
+    }
+
+    #[inline]
+    pub(crate) fn on_remove(&self, component_id: ComponentId) -> ComponentCallback {
+        self.component_on_remove[component_id.0]
+    }
+
+    #[inline]
+    pub(crate) fn raw_clone_fn(&self, component_id: ComponentId) -> Option<RawCloneFn> {
+        self.component_raw_clone[component_id.0]
+    }
 
-        Some(unsafe { sparse_set.remove_as::<C>(entity.id() as usize) }.expect("component manager remove_component id missing"))
+    #[inline]
+    pub(crate) fn layout(&self, component_id: ComponentId) -> Layout {
+        self.components[component_id.0].item_layout()
+    }
+
+    #[inline]
+    pub(crate) fn name(&self, component_id: ComponentId) -> Option<&str> {
+        self.component_names.get(component_id.0).map(|name| name.as_ref())
+    }
+
+    #[inline]
+    pub(crate) fn is_mutable(&self, component_id: ComponentId) -> bool {
+        self.component_mutable[component_id.0]
     }
 
+    #[inline]
+    pub(crate) const fn len(&self) -> usize {
+        self.component_len
+    }
+
+    /// Starts indexing the component, filled with the entities that already have it
+    pub(crate) fn register_index<C: IndexableComponent>(&mut self) -> ComponentId {
+        let component_id = self.register_component::<C>();
+        if self.component_index[component_id.0].is_some() { return component_id; }
+        let mut index = ErasedComponentIndex::new::<C>();
+        for entity in self.groups.iter().filter(|(signature, _)| signature.get(component_id.0)).flat_map(|(_, entities)| entities.iter().copied()) {
+            let value = self.get_ptr_by_id(entity, component_id).expect("component manager register_index component missing");
+            unsafe { index.insert(entity, value) };
+        }
+        self.component_index[component_id.0] = Some(index);
+        component_id
+    }
+
+    #[inline]
+    pub(crate) fn index<C: IndexableComponent>(&self) -> Option<&ComponentIndex<C>> {
+        let component_id = self.get_component_id::<C>()?;
+        unsafe { self.index_by_id(component_id) }
+    }
+
+    /// # Safety
+    /// Component_id must correspond to the component C
+    #[inline]
+    pub(crate) unsafe fn index_by_id<C: IndexableComponent>(&self, component_id: ComponentId) -> Option<&ComponentIndex<C>> {
+        self.component_index[component_id.0].as_ref().map(|index| unsafe { index.get::<C>() })
+    }
+
+    #[inline]
+    fn index_insert(&mut self, entity: Entity, component_id: ComponentId) {
+        // taken out while inserting, the value may live in a table
+        let Some(mut index) = self.component_index[component_id.0].take() else { return; };
+        let value = self.get_ptr_by_id(entity, component_id).expect("component manager index_insert component missing");
+        unsafe { index.insert(entity, value) };
+        self.component_index[component_id.0] = Some(index);
+    }
+
+    // must run while the entity still has its value, before it is replaced or removed
+    #[inline]
+    fn index_remove(&mut self, entity: Entity, component_id: ComponentId) {
+        let Some(mut index) = self.component_index[component_id.0].take() else { return; };
+        if let Some(value) = self.get_ptr_by_id(entity, component_id) {
+            unsafe { index.remove(entity, value) };
+        }
+        self.component_index[component_id.0] = Some(index);
+    }
+
+    /// Reindexes the mutable components changed in place since the last refresh
+    pub(crate) fn refresh_indexes(&mut self, change_tick: Tick) {
+        for component_id in (0..self.component_index.len()).map(ComponentId) {
+            let Some(mut index) = self.component_index[component_id.0].take() else { continue; };
+            unsafe { index.refresh(self, component_id, change_tick) };
+            self.component_index[component_id.0] = Some(index);
+        }
+    }
+
+    /// Entity must be alive, moves it to the group and table without the signature's components in one step and returns the ones it had
+    /// Their values are dropped running their remove hooks, without commands they must have been read out by take_value
+    pub(crate) fn remove_signature(&mut self, entity: Entity, signature: &Signature, mut commands: Option<&mut Commands>) -> Option<Signature> {
+        let group_id = *self.entity_groups.get(entity.id() as usize)?;
+        let removed = self.groups.signature(group_id) & signature;
+        if removed.is_zero() {
+            return Some(removed);
+        }
+        let target = match removed.iter_ones().nth(1) {
+            None => self.groups.without_component(group_id, removed.iter_ones().next().expect("component manager remove_signature empty signature")),
+            Some(_) => {
+                let mut entity_signature = self.groups.signature(group_id).clone();
+                entity_signature.difference_with(signature);
+                self.groups.group_id(&entity_signature)
+            }
+        };
+        self.move_group(entity, group_id, target);
+        for component_id in removed.iter_ones().map(ComponentId) {
+            self.index_remove(entity, component_id);
+        }
+        self.remove_signature_from_table(entity, &removed, commands.as_deref_mut());
+        if let Some(commands) = commands {
+            let mut sparse = removed.clone();
+            sparse.difference_with(&self.table_components);
+            for component_id in sparse.iter_ones().map(ComponentId) {
+                self.remove_ungrouped(entity, component_id, commands);
+            }
+        }
+        Some(removed)
+    }
+
+    // moves the entity to the group with the component, if it isn't there already
+    #[inline]
+    fn group_with(&mut self, entity: Entity, component_id: ComponentId) {
+        let group_id = *self.entity_groups.get(entity.id() as usize).expect("entity doesnt belong to any groups");
+        if self.groups.signature(group_id).get(component_id.0) { return; }
+        let target = self.groups.with_component(group_id, component_id.0);
+        self.move_group(entity, group_id, target);
+    }
+
+    #[inline]
+    fn move_group(&mut self, entity: Entity, from: usize, to: usize) {
+        self.groups.entities_mut(from).remove(entity.id() as usize);
+        self.groups.entities_mut(to).insert(entity.id() as usize, entity);
+        *self.entity_groups.get_mut(entity.id() as usize).expect("entity doesnt belong to any groups") = to;
+    }
+
+    // drops the sparse set value of an entity that was already moved out of the component's group, running its hooks
+    fn remove_ungrouped(&mut self, entity: Entity, component_id: ComponentId, commands: &mut Commands) {
+        let hooks = self.component_hooks[component_id.0];
+        let context = HookContext { entity, component_id };
+        hooks.run_replace(commands, context);
+        let ptr = self.components[component_id.0].get_mut_ptr(entity.id() as usize).expect("component manager remove_ungrouped id missing");
+        (self.component_on_remove[component_id.0])(ptr, commands);
+        self.components[component_id.0].remove(entity.id() as usize);
+        hooks.run_remove(commands, context);
+    }
+
+    /// Groups the entity under the signature and gives it the last row of the table of its table components
+    /// # Safety
+    /// Entity must be alive and not have any components yet, every component of the signature must then be written with write_spawned
     pub(crate) unsafe fn insert_empty_entity(&mut self, entity: Entity, signature: Signature) {
-        assert!(self.entity_signatures.insert(entity.id() as usize, signature).is_none(), "component manager duplicate EntityId");
-        let group = self.groups.entry(signature).or_default();
-        group.insert(entity.id() as usize, entity);
+        let group_id = self.groups.group_id(&signature);
+        assert!(self.entity_groups.insert(entity.id() as usize, group_id).is_none(), "component manager duplicate EntityId");
+        self.groups.entities_mut(group_id).insert(entity.id() as usize, entity);
+        let table_id = self.table_id(&signature & &self.table_components);
+        self.tables[table_id].push_entity(entity);
+        self.entity_tables.insert(entity.id() as usize, table_id);
     }
 
-    /// Entity must be alive
-    pub(crate) unsafe fn set_component_unchecked<C: Component>(&mut self, entity: Entity, component: C) -> Option<C> {
+    /// # Safety
+    /// Entities must be alive and not have any components yet, every component of the signature must then be written with write_spawned
+    /// in the order the entities were given
+    pub(crate) unsafe fn insert_empty_entities(&mut self, entities: &[Entity], signature: Signature) {
+        let group_id = self.groups.group_id(&signature);
+        let group = self.groups.entities_mut(group_id);
+        group.reserve(entities.len());
+        for entity in entities.iter().copied() {
+            assert!(self.entity_groups.insert(entity.id() as usize, group_id).is_none(), "component manager duplicate EntityId");
+            group.insert(entity.id() as usize, entity);
+        }
+        let table_id = self.table_id(&signature & &self.table_components);
+        self.tables[table_id].reserve(entities.len());
+        for entity in entities.iter().copied() {
+            self.tables[table_id].push_entity(entity);
+            self.entity_tables.insert(entity.id() as usize, table_id);
+        }
+    }
+
+    // table columns are reserved together with their table by insert_empty_entities
+    pub(crate) fn reserve<C: Component>(&mut self, additional: usize) {
+        let component_id = self.register_component::<C>();
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            self.components[component_id.0].reserve(additional);
+        }
+    }
+
+    /// Writes a component of an entity inserted with insert_empty_entity or insert_empty_entities, pushing its column at the entity's row
+    /// # Safety
+    /// Entity must have been inserted under a signature containing the component
+    pub(crate) unsafe fn write_spawned<C: Component>(&mut self, entity: Entity, component: C, tick: Tick) -> Option<C> {
         let component_record = unsafe { self.component_records.get(&TypeId::of::<C>()).unwrap_unchecked() };
         let component_id = component_record.id;
-        let sparse_set = &mut self.components[component_id.0];
-        unsafe { sparse_set.insert(entity.id() as usize, component) }
+        let replaced = unsafe { self.write_value(entity, component_id, component, tick) };
+        // a bundle listing the component twice replaces the value indexed by its first write
+        if let Some(replaced) = &replaced && let Some(index) = self.component_index[component_id.0].as_mut() {
+            unsafe { index.remove(entity, Ptr::new(NonNull::from(replaced).cast())) };
+        }
+        self.index_insert(entity, component_id);
+        replaced
     }
 
     /// Entity must be alive
     pub(crate) fn despawn(&mut self, entity: Entity, mut commands: Commands) {
-        let Some(entity_signature) = self.entity_signatures.remove(entity.id() as usize) else { return; };
-        let group = self.groups.get_mut(&entity_signature).expect("entity doesnt belong to any groups");
-        group.remove(entity.id() as usize);
-        
-        let mut entity_signature_raw = *entity_signature;
-        let mut index = 0;
-        while entity_signature_raw > 0 {
-            if (entity_signature_raw & 1) == 1 {
-                let ptr = self.components[index].get_mut_ptr(entity.id() as usize).expect("component manager despawn id missing");
-                (self.component_on_remove[index])(ptr, &mut commands);
-                self.components[index].remove(entity.id() as usize);
-            }
-            entity_signature_raw >>= 1;
-            index += 1;
+        let Some(group_id) = self.entity_groups.remove(entity.id() as usize) else { return; };
+        self.groups.entities_mut(group_id).remove(entity.id() as usize);
+        let entity_signature = self.groups.signature(group_id).clone();
+        for component_id in entity_signature.iter_ones().map(ComponentId) {
+            self.component_hooks[component_id.0].run_despawn(&mut commands, HookContext { entity, component_id });
+            self.index_remove(entity, component_id);
+        }
+        if let Some(table_id) = self.entity_tables.remove(entity.id() as usize) {
+            self.tables[table_id].swap_remove(entity.id() as usize, |component_id, value, _| unsafe {
+                let context = HookContext { entity, component_id: ComponentId(component_id) };
+                drop_removed(value, context, self.component_hooks[component_id], self.component_on_remove[component_id], self.components[component_id].drop_fn(), &mut commands);
+            });
+        }
+        let mut sparse = entity_signature;
+        sparse.difference_with(&self.table_components);
+        for component_id in sparse.iter_ones().map(ComponentId) {
+            self.remove_ungrouped(entity, component_id, &mut commands);
         }
     }
 
     pub(crate) fn get_component_signature(&self, type_id: &TypeId) -> Option<Signature> {
         let record = self.component_records.get(type_id)?;
-        Some(record.signature)
+        Some(record.signature.clone())
     }
 
     pub(crate) fn get_component_id<C: Component>(&self) -> Option<ComponentId> {
@@ -177,95 +886,305 @@ impl Components {
     /// Entity must be alive
     /// Component_id must correspond to a component array of type C
     pub(crate) unsafe fn get_component_by_id<C: Component>(&self, entity: Entity, component_id: ComponentId) -> Option<&C> {
-        let sparse_set = &self.components[component_id.0];
-        unsafe { sparse_set.get(entity.id() as usize) }
+        self.get_ptr_by_id(entity, component_id).map(|ptr| unsafe { ptr.cast_ref::<C>() })
     }
 
     /// # Safety
     /// Entity must be alive
     /// Component_id must correspond to a component array of type C
     pub(crate) unsafe fn get_component_by_id_unchecked<C: Component>(&self, entity: Entity, component_id: ComponentId) -> &C {
-        let sparse_set = &self.components[component_id.0];
-        unsafe { sparse_set.get(entity.id() as usize).unwrap() }
+        unsafe { self.get_component_by_id(entity, component_id).unwrap() }
+    }
+
+    /// # Safety
+    /// Entity must be alive
+    /// Component_id must correspond to a component array of type C
+    pub(crate) unsafe fn get_mut_component_by_id<C: Component>(&mut self, entity: Entity, component_id: ComponentId, tick: Tick) -> Option<&mut C> {
+        let (component, ticks) = unsafe { self.get_mut_component_with_ticks_by_id(entity, component_id) }?;
+        ticks.changed = tick;
+        Some(component)
     }
 
     /// # Safety
     /// Entity must be alive
     /// Component_id must correspond to a component array of type C
-    pub(crate) unsafe fn get_mut_component_by_id<C: Component>(&mut self, entity: Entity, component_id: ComponentId) -> Option<&mut C> {
-        let sparse_set = &mut self.components[component_id.0];
-        unsafe { sparse_set.get_mut(entity.id() as usize) }
+    pub(crate) unsafe fn get_mut_component_by_id_unchecked<C: Component>(&mut self, entity: Entity, component_id: ComponentId, tick: Tick) -> &mut C {
+        unsafe { self.get_mut_component_by_id(entity, component_id, tick).unwrap() }
     }
 
     /// # Safety
     /// Entity must be alive
     /// Component_id must correspond to a component array of type C
-    pub(crate) unsafe fn get_mut_component_by_id_unchecked<C: Component>(&mut self, entity: Entity, component_id: ComponentId) -> &mut C {
-        let sparse_set = &mut self.components[component_id.0];
-        unsafe { sparse_set.get_mut(entity.id() as usize).unwrap() }
+    pub(crate) unsafe fn get_component_with_ticks_by_id<C: Component>(&self, entity: Entity, component_id: ComponentId) -> Option<(&C, &ComponentTicks)> {
+        self.get_ptr_with_ticks_by_id(entity, component_id).map(|(ptr, ticks)| (unsafe { ptr.cast_ref::<C>() }, ticks))
+    }
+
+    /// # Safety
+    /// Entity must be alive
+    /// Component_id must correspond to a component array of type C
+    pub(crate) unsafe fn get_mut_component_with_ticks_by_id<C: Component>(&mut self, entity: Entity, component_id: ComponentId) -> Option<(&mut C, &mut ComponentTicks)> {
+        self.get_mut_ptr_with_ticks_by_id(entity, component_id).map(|(mut ptr, ticks)| (unsafe { ptr.cast_mut::<C>() }, ticks))
+    }
+
+    /// Entity must be alive
+    #[inline]
+    pub(crate) fn get_component_ticks(&self, entity: Entity, component_id: ComponentId) -> Option<ComponentTicks> {
+        self.get_ptr_with_ticks_by_id(entity, component_id).map(|(_, ticks)| *ticks)
+    }
+
+    pub(crate) fn set_clone_fn(&mut self, component_id: ComponentId, f: ComponentCloneFn) {
+        self.component_clone[component_id.0] = Some(f);
+    }
+
+    #[inline]
+    pub(crate) fn clone_fn(&self, component_id: ComponentId) -> Option<ComponentCloneFn> {
+        self.component_clone[component_id.0]
+    }
+
+    #[inline]
+    pub(crate) fn hooks(&self, component_id: ComponentId) -> ComponentHooks {
+        self.component_hooks[component_id.0]
+    }
+
+    #[inline]
+    pub(crate) fn hooks_mut(&mut self, component_id: ComponentId) -> &mut ComponentHooks {
+        &mut self.component_hooks[component_id.0]
+    }
+
+    /// Whether the lifecycle trigger of the component has observers
+    #[inline]
+    pub(crate) fn is_observed(&mut self, component_id: ComponentId, event: LifecycleEvent, observers: &Observers) -> bool {
+        let lifecycle = &mut self.component_lifecycle[component_id.0];
+        let Some(event_type_ids) = lifecycle.event_type_ids else { return false; };
+        if lifecycle.generation != observers.generation() {
+            lifecycle.observed = event_type_ids.map(|type_id| observers.is_observed(&type_id));
+            lifecycle.generation = observers.generation();
+        }
+        lifecycle.observed[event as usize]
+    }
+
+    /// Transitive requirements of the component
+    #[inline]
+    pub(crate) fn required_components(&self, component_id: ComponentId) -> &[RequiredComponent] {
+        &self.component_required[component_id.0]
+    }
+
+    /// Requirements of the components in the signature that it doesn't contain itself
+    pub(crate) fn missing_required_components(&self, signature: &Signature) -> Vec<RequiredComponent> {
+        let mut missing: Vec<RequiredComponent> = Vec::new();
+        for index in signature.iter_ones() {
+            for required in self.component_required[index].iter() {
+                if !signature.get(required.id.0) && !missing.iter().any(|missing| missing.id == required.id) {
+                    missing.push(*required);
+                }
+            }
+        }
+        missing
+    }
+
+    #[inline]
+    pub(crate) fn lifecycle_trigger(&self, component_id: ComponentId, event: LifecycleEvent) -> fn(&mut World, Entity) {
+        self.component_lifecycle[component_id.0].triggers[event as usize]
     }
 
+    /// Entity must be alive
     #[inline]
-    pub(crate) fn groups(&self) -> &HashMap<Signature, SparseSet<Entity>> {
+    pub(crate) fn contains(&self, entity: Entity, component_id: ComponentId) -> bool {
+        self.table_location(entity, component_id).is_some() || self.components[component_id.0].contains(entity.id() as usize)
+    }
+
+    /// Clamps the ticks of every stored value, see [`Tick::check_tick`]
+    pub(crate) fn check_change_ticks(&mut self, change_tick: Tick) {
+        for sparse_set in self.components.iter_mut() {
+            sparse_set.check_ticks(change_tick);
+        }
+        for table in self.tables.iter_mut() {
+            table.check_ticks(change_tick);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn groups(&self) -> &Groups {
         &self.groups
     }
 
+    #[inline]
+    pub(crate) fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    #[inline]
+    pub(crate) fn tables_mut(&mut self) -> &mut [Table] {
+        &mut self.tables
+    }
+
+    #[inline]
+    pub(crate) const fn table_components(&self) -> &Signature {
+        &self.table_components
+    }
+
+    /// Whether the entity has the component, which must be stored in a sparse set
+    #[inline]
+    pub(crate) fn sparse_contains(&self, entity: Entity, component_id: usize) -> bool {
+        self.components[component_id].contains(entity.id() as usize)
+    }
+
+    /// Table of the entities with this signature, if one was created
+    // for testing purposes
+    #[cfg(test)]
+    #[inline]
+    pub(crate) fn get_table_id(&self, signature: &Signature) -> Option<usize> {
+        self.table_ids.get(&(signature & &self.table_components)).copied()
+    }
+
     /// Entity must be alive
     pub(crate) fn get_entity_signature_by_type_id(&self, entity: Entity) -> Option<Signature> {
-        self.entity_signatures.get(entity.id() as usize).copied()
+        self.entity_groups.get(entity.id() as usize).map(|group_id| self.groups.signature(*group_id).clone())
     }
 }
 
+// transitions between the tables, by table id
+impl Transitions for Components {
+    #[inline]
+    fn signature(&self, table_id: usize) -> &Signature {
+        self.tables[table_id].signature()
+    }
 
-pub trait ComponentBundle {
-    fn spawn(self, entity: Entity, world: &mut World);
-    fn signature(world: &mut World) -> Signature;
+    #[inline]
+    fn edges(&self, table_id: usize) -> &Edges {
+        self.tables[table_id].edges()
+    }
+
+    #[inline]
+    fn edges_mut(&mut self, table_id: usize) -> &mut Edges {
+        self.tables[table_id].edges_mut()
+    }
+
+    #[inline]
+    fn get_or_insert(&mut self, signature: Signature) -> usize {
+        self.table_id(signature)
+    }
 }
 
-impl<C: Component + 'static> ComponentBundle for C {
+
+pub trait ComponentBundle: Sized {
     fn spawn(self, entity: Entity, world: &mut World) {
-        let signature = world.register_component::<C>().as_signature();
+        let signature = Self::signature(world);
         unsafe {
-            world.insert_empty_entity(entity, signature);
-            world.set_component_unchecked(entity, self);
+            let required = world.insert_empty_entity(entity, signature);
+            self.write_components(entity, world);
+            world.write_required_components(entity, &required);
+            world.finish_spawn(entity, &required);
         }
+        Self::trigger_added(entity, world);
     }
+    fn signature(world: &mut World) -> Signature;
+    /// Adds the components to an existing entity, replacing the ones it already has
+    fn insert(self, entity: Entity, world: &mut World);
+    /// Adds the components the entity doesn't have yet, leaving existing ones untouched
+    fn insert_if_new(self, entity: Entity, world: &mut World);
+    /// Reserves storage for additional bundles
+    fn reserve(world: &mut World, additional: usize);
+    /// Writes the components of a spawned entity without running their hooks, see [`World::finish_spawn`]
+    /// # Safety
+    /// Entity must be alive and spawned under a signature containing the bundle's
+    unsafe fn write_components(self, entity: Entity, world: &mut World);
+    /// Triggers the add and insert observers of the components, once the whole bundle is written
+    fn trigger_added(entity: Entity, world: &mut World);
+    /// Reads the components out of the entity, which must then be moved out of their groups and tables forgetting their table values
+    /// # Safety
+    /// Entity must be alive and have all of the bundle's components
+    unsafe fn take_components(entity: Entity, world: &mut World) -> Self;
+}
 
+impl<C: Component + 'static> ComponentBundle for C {
     fn signature(world: &mut World) -> Signature {
         world.register_component::<C>().as_signature()
     }
+
+    fn insert(self, entity: Entity, world: &mut World) {
+        world.set_component(entity, self);
+    }
+
+    fn insert_if_new(self, entity: Entity, world: &mut World) {
+        if world.get_component::<C>(entity).is_none() {
+            world.set_component(entity, self);
+        }
+    }
+
+    fn reserve(world: &mut World, additional: usize) {
+        world.reserve_components::<C>(additional);
+    }
+
+    unsafe fn write_components(self, entity: Entity, world: &mut World) {
+        unsafe { world.write_spawned_component(entity, self) };
+    }
+
+    #[inline]
+    fn trigger_added(entity: Entity, world: &mut World) {
+        world.trigger_added::<C>(entity);
+    }
+
+    unsafe fn take_components(entity: Entity, world: &mut World) -> Self {
+        unsafe { world.take_component_value::<C>(entity) }
+    }
 }
 
 macro_rules! bundle_tuple_impl {
     ($(($idx:tt, $name:ident)),+) => {
-        impl<$($name: Component + 'static),+> ComponentBundle for ($($name),+) {
-            fn spawn(self, entity: Entity, world: &mut World) {
-                let data = self;
-                let mut signature = Bitmap::new();
-                $(signature |= world.register_component::<$name>().as_signature();)+
-                unsafe {
-                    world.insert_empty_entity(entity, signature);
-                    $(world.set_component_unchecked(entity, data.$idx));+;
-                }
-            }
-
+        impl<$($name: ComponentBundle),+> ComponentBundle for ($($name),+) {
             fn signature(world: &mut World) -> Signature {
                 let mut signature = Signature::new();
-                $(signature |= world.register_component::<$name>().as_signature();)+
+                $(signature |= &$name::signature(world);)+
                 signature
             }
+
+            fn insert(self, entity: Entity, world: &mut World) {
+                let data = self;
+                $(data.$idx.insert(entity, world);)+
+            }
+
+            fn insert_if_new(self, entity: Entity, world: &mut World) {
+                let data = self;
+                $(data.$idx.insert_if_new(entity, world);)+
+            }
+
+            fn reserve(world: &mut World, additional: usize) {
+                $($name::reserve(world, additional);)+
+            }
+
+            unsafe fn write_components(self, entity: Entity, world: &mut World) {
+                let data = self;
+                unsafe { $(data.$idx.write_components(entity, world));+; }
+            }
+
+            fn trigger_added(entity: Entity, world: &mut World) {
+                $($name::trigger_added(entity, world);)+
+            }
+
+            unsafe fn take_components(entity: Entity, world: &mut World) -> Self {
+                unsafe { ($($name::take_components(entity, world)),+) }
+            }
         }
     }
 }
 
 impl ComponentBundle for () {
-    fn spawn(self, entity: Entity, world: &mut World) {
-        unsafe { world.insert_empty_entity(entity, Signature::new()) };
-    }
-
     fn signature(_: &mut World) -> Signature {
         Signature::new()
     }
+
+    fn insert(self, _: Entity, _: &mut World) {}
+
+    fn insert_if_new(self, _: Entity, _: &mut World) {}
+
+    fn reserve(_: &mut World, _: usize) {}
+
+    unsafe fn write_components(self, _: Entity, _: &mut World) {}
+
+    fn trigger_added(_: Entity, _: &mut World) {}
+
+    unsafe fn take_components(_: Entity, _: &mut World) -> Self {}
 }
 
 variadics_please::all_tuples_enumerated!{bundle_tuple_impl, 2, 32, C}
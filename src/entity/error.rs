@@ -0,0 +1,95 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{Entity, Name, NamedEntity};
+
+#[derive(Clone, Debug)]
+pub struct HierarchyError {
+    kind: HierarchyErrorKind,
+    // names of the kind's entities, resolved by the world reporting the error
+    names: Vec<(Entity, Name)>,
+}
+
+impl HierarchyError {
+    pub(crate) fn self_parent(entity: Entity) -> Self {
+        Self {
+            kind: HierarchyErrorKind::SelfParent(entity),
+            names: Vec::new(),
+        }
+    }
+
+    pub(crate) fn cycle(parent: Entity, child: Entity) -> Self {
+        Self {
+            kind: HierarchyErrorKind::Cycle { parent, child },
+            names: Vec::new(),
+        }
+    }
+
+    /// Resolves the names of the error's entities, which Display prints next to them
+    pub(crate) fn with_names(mut self, name: impl Fn(Entity) -> Option<Name>) -> Self {
+        let entities = match self.kind {
+            HierarchyErrorKind::SelfParent(entity) => vec![entity],
+            HierarchyErrorKind::Cycle { parent, child } => vec![parent, child],
+        };
+        self.names = entities.into_iter().filter_map(|entity| Some((entity, name(entity)?))).collect();
+        self
+    }
+
+    fn named(&self, entity: Entity) -> NamedEntity<'_> {
+        NamedEntity::new(entity, self.names.iter().find(|(named, _)| *named == entity).map(|(_, name)| name))
+    }
+
+    #[inline]
+    pub fn kind(&self) -> &HierarchyErrorKind {
+        &self.kind
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyErrorKind {
+    SelfParent(Entity),
+    Cycle { parent: Entity, child: Entity },
+}
+
+impl Display for HierarchyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            HierarchyErrorKind::SelfParent(entity) => f.write_fmt(format_args!("entity '{:?}' tried to be it's own parent", self.named(entity))),
+            HierarchyErrorKind::Cycle { parent, child } => f.write_fmt(format_args!("entity '{:?}' can't be the parent of '{:?}', it would create a cycle", self.named(parent), self.named(child))),
+        }
+    }
+}
+
+impl Error for HierarchyError {}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EntityError {
+    kind: EntityErrorKind,
+}
+
+impl EntityError {
+    pub(crate) fn dead(entity: Entity) -> Self {
+        Self {
+            kind: EntityErrorKind::Dead(entity),
+        }
+    }
+
+    #[inline]
+    pub fn kind(&self) -> &EntityErrorKind {
+        &self.kind
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntityErrorKind {
+    Dead(Entity),
+}
+
+impl Display for EntityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            EntityErrorKind::Dead(entity) => f.write_fmt(format_args!("entity '{:?}' is not alive", entity)),
+        }
+    }
+}
+
+impl Error for EntityError {}
@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use crate::Entity;
+
+/// Maps entities of a cloned subtree to their copies
+#[derive(Default, Debug)]
+pub struct EntityMap {
+    map: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    pub(crate) fn insert(&mut self, source: Entity, target: Entity) {
+        self.map.insert(source, target);
+    }
+
+    /// Returns the copy of entity, entities outside of the mapping are returned unchanged
+    #[inline]
+    pub fn get(&self, entity: Entity) -> Entity {
+        self.map.get(&entity).copied().unwrap_or(entity)
+    }
+
+    #[inline]
+    pub fn get_mapped(&self, entity: Entity) -> Option<Entity> {
+        self.map.get(&entity).copied()
+    }
+}
+
+/// Components holding entity references implement this to have them remapped when cloned with
+/// [`crate::World::register_component_clone_mapped`]
+pub trait MapEntities {
+    fn map_entities(&mut self, map: &EntityMap);
+}
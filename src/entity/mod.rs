@@ -0,0 +1,449 @@
+pub mod error;
+pub mod map;
+pub mod name;
+use std::{collections::VecDeque, sync::atomic::{AtomicUsize, Ordering}};
+
+use crate::{Commands, entity::error::HierarchyError, storage::sparse_set::{SparseIndex, SparseSet}};
+
+// one below u32::MAX so every entity fits a sparse set, whose dense indices use u32::MAX as none
+pub const MAX_ENTITY_ID: usize = SparseIndex::MAX;
+pub const MAX_ENTITY_VERSION: u32 = u32::MAX - 1;
+// versions of retired slots, never handed out to a live entity
+const RETIRED_VERSION: u32 = u32::MAX;
+
+#[derive(Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Entity {
+    id: u32,
+    version: u32,
+}
+
+impl Entity {
+    #[inline(always)]
+    const fn new(id: u32, version: u32) -> Self {
+        Self { id, version }
+    }
+
+    #[inline(always)]
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[inline(always)]
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Packs the entity into a single u64, id in the low half and version in the high half
+    #[inline(always)]
+    pub const fn to_bits(&self) -> u64 {
+        (self.version as u64) << 32 | self.id as u64
+    }
+
+    /// Unpacks an entity from [`Entity::to_bits`], None for the version of retired slots which no entity ever has
+    #[inline(always)]
+    pub const fn from_bits(bits: u64) -> Option<Self> {
+        let version = (bits >> 32) as u32;
+        if version == RETIRED_VERSION { return None; }
+        Some(Self::new(bits as u32, version))
+    }
+}
+
+/// Prints `{id}v{version}`, see [`crate::World::named`] for output including the entity's name
+impl std::fmt::Debug for Entity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}v{}", self.id(), self.version()))
+    }
+}
+
+/// What happens to the children of an entity when it is despawned
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HierarchyDespawnPolicy {
+    /// Children are despawned together with their whole subtrees
+    #[default]
+    Recursive,
+    /// Children lose their parent and become roots
+    DetachChildren,
+    /// Children are moved to the despawned entity's parent, taking its place among the siblings.
+    /// Roots detach their children instead.
+    ReparentChildren,
+}
+
+/// Order in which the ids of despawned entities are handed out again
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum EntityRecyclingPolicy {
+    /// The most recently freed id is reused first
+    #[default]
+    Lifo,
+    /// The least recently freed id is reused first
+    Fifo,
+    /// Freed ids are held back until at least N more entities were spawned, then reused oldest first.
+    /// Held ids are released on despawns and schedule runs.
+    QuarantineSpawns(usize),
+    /// Freed ids are held back for N schedule runs, then reused oldest first
+    QuarantineScheduleRuns(usize),
+}
+
+pub struct Entities {
+    free_entity_ids: VecDeque<u32>,
+    entity_versions: Vec<u32>,
+    claimed_free_entities: AtomicUsize,
+    highest_free_entity_id: AtomicUsize,
+    recycling_policy: EntityRecyclingPolicy,
+    // freed ids with the spawn count or schedule run at which they are released
+    quarantine: VecDeque<(u32, usize)>,
+    spawn_count: AtomicUsize,
+    schedule_runs: usize,
+    // TODO: might want to consider a different structure than Vec for fast removal and unique insertion
+    // children are kept in insertion order, removal has to preserve it
+    children: SparseSet<Vec<Entity>>,
+    parents: SparseSet<Entity>,
+}
+
+impl Default for Entities {
+    fn default() -> Self {
+        Self {
+            free_entity_ids: VecDeque::new(),
+            entity_versions: Vec::new(),
+            claimed_free_entities: AtomicUsize::new(0),
+            highest_free_entity_id: AtomicUsize::new(0),
+            recycling_policy: EntityRecyclingPolicy::default(),
+            quarantine: VecDeque::new(),
+            spawn_count: AtomicUsize::new(0),
+            schedule_runs: 0,
+            children: SparseSet::default(),
+            parents: SparseSet::default(),
+        }
+    }
+}
+
+impl Entities {
+    pub fn despawn(&mut self, entity: Entity, policy: HierarchyDespawnPolicy, command_buffer: &mut Vec<u8>) {
+        self.flush_claimed();
+        if entity.id() as usize >= self.entity_versions.len() {
+            self.entity_versions.resize(entity.id() as usize + 1, 0);
+        }
+        let version = &mut self.entity_versions[entity.id() as usize];
+        if *version >= MAX_ENTITY_VERSION {
+            // the slot ran out of versions, reusing it would make stale handles alive again
+            *version = RETIRED_VERSION;
+        } else {
+            *version += 1;
+            self.free(entity.id());
+        }
+        self.release_quarantined();
+
+        let parent = self.parents.remove(entity.id() as usize);
+        let position = parent.and_then(|parent| self.detach_from_children_list(parent, entity));
+
+        let Some(children) = self.children.remove(entity.id() as usize) else { return; };
+        match (policy, parent, position) {
+            (HierarchyDespawnPolicy::Recursive, _, _) => {
+                for child in children.iter() {
+                    self.parents.remove(child.id() as usize);
+                }
+                let mut commands = Commands::new(command_buffer, self);
+                for entity in children {
+                    commands.despawn_recursive(entity);
+                }
+            },
+            (HierarchyDespawnPolicy::ReparentChildren, Some(parent), Some(position)) => {
+                for child in children.iter() {
+                    self.parents.insert(child.id() as usize, parent);
+                }
+                let siblings = self.children.entry(parent.id() as usize).or_default();
+                siblings.splice(position..position, children);
+            },
+            _ => {
+                for child in children {
+                    self.parents.remove(child.id() as usize);
+                }
+            },
+        }
+    }
+
+    // removes the ids claimed by spawn since the last flush from the free list
+    fn flush_claimed(&mut self) {
+        let claimed = std::mem::take(self.claimed_free_entities.get_mut()).min(self.free_entity_ids.len());
+        match self.recycling_policy {
+            EntityRecyclingPolicy::Lifo => self.free_entity_ids.truncate(self.free_entity_ids.len() - claimed),
+            _ => { self.free_entity_ids.drain(..claimed); },
+        }
+    }
+
+    fn free(&mut self, id: u32) {
+        match self.recycling_policy {
+            EntityRecyclingPolicy::QuarantineSpawns(spawns) => {
+                let release_at = self.spawn_count.get_mut().saturating_add(spawns);
+                self.quarantine.push_back((id, release_at));
+            },
+            EntityRecyclingPolicy::QuarantineScheduleRuns(runs) => {
+                self.quarantine.push_back((id, self.schedule_runs.saturating_add(runs)));
+            },
+            _ => self.free_entity_ids.push_back(id),
+        }
+    }
+
+    // must be called after flush_claimed
+    fn release_quarantined(&mut self) {
+        let now = match self.recycling_policy {
+            EntityRecyclingPolicy::QuarantineSpawns(_) => *self.spawn_count.get_mut(),
+            EntityRecyclingPolicy::QuarantineScheduleRuns(_) => self.schedule_runs,
+            _ => usize::MAX,
+        };
+        while let Some((id, release_at)) = self.quarantine.front().copied() && release_at <= now {
+            self.quarantine.pop_front();
+            self.free_entity_ids.push_back(id);
+        }
+    }
+
+    pub(crate) fn end_schedule_run(&mut self) {
+        self.schedule_runs += 1;
+        if self.quarantine.is_empty() { return; }
+        self.flush_claimed();
+        self.release_quarantined();
+    }
+
+    #[inline]
+    pub const fn recycling_policy(&self) -> EntityRecyclingPolicy {
+        self.recycling_policy
+    }
+
+    /// Ids held in quarantine by the previous policy become free immediately
+    pub fn set_recycling_policy(&mut self, policy: EntityRecyclingPolicy) {
+        self.flush_claimed();
+        self.free_entity_ids.extend(self.quarantine.drain(..).map(|(id, _)| id));
+        self.recycling_policy = policy;
+    }
+
+    pub fn spawn(&self) -> Entity {
+        if let EntityRecyclingPolicy::QuarantineSpawns(_) = self.recycling_policy {
+            self.spawn_count.fetch_add(1, Ordering::Relaxed);
+        }
+        if self.free_entity_ids.is_empty() {
+            let highest_free_entity_id = self.highest_free_entity_id.fetch_add(1, Ordering::Relaxed);
+            assert!(highest_free_entity_id <= MAX_ENTITY_ID, "entity overflow");
+            Entity::new(highest_free_entity_id as u32, 0)
+        } else {
+            let claimed = self.claimed_free_entities.fetch_add(1, Ordering::Relaxed);
+            let free_entity_index = match self.recycling_policy {
+                EntityRecyclingPolicy::Lifo => (self.free_entity_ids.len() - 1).checked_sub(claimed),
+                _ => (claimed < self.free_entity_ids.len()).then_some(claimed),
+            };
+            if let Some(free_entity_index) = free_entity_index {
+                let entity_id = self.free_entity_ids[free_entity_index];
+                let version = self.entity_versions[entity_id as usize];
+                Entity::new(entity_id, version)
+            } else {
+                let highest_free_entity_id = self.highest_free_entity_id.fetch_add(1, Ordering::Relaxed);
+                assert!(highest_free_entity_id <= MAX_ENTITY_ID, "entity overflow");
+                Entity::new(highest_free_entity_id as u32, 0)
+            }
+        }
+    }
+
+    /// Reserves count entities at once, recycled ids are handed out first
+    pub fn spawn_batch(&self, count: usize) -> Vec<Entity> {
+        if let EntityRecyclingPolicy::QuarantineSpawns(_) = self.recycling_policy {
+            self.spawn_count.fetch_add(count, Ordering::Relaxed);
+        }
+        let mut entities = Vec::with_capacity(count);
+        let free_len = self.free_entity_ids.len();
+        let claimed = if free_len == 0 { free_len } else { self.claimed_free_entities.fetch_add(count, Ordering::Relaxed) };
+        let recycled = free_len.saturating_sub(claimed).min(count);
+        for i in claimed..claimed + recycled {
+            let free_entity_index = match self.recycling_policy {
+                EntityRecyclingPolicy::Lifo => free_len - 1 - i,
+                _ => i,
+            };
+            let entity_id = self.free_entity_ids[free_entity_index];
+            entities.push(Entity::new(entity_id, self.entity_versions[entity_id as usize]));
+        }
+        let fresh = count - recycled;
+        if fresh > 0 {
+            let first = self.highest_free_entity_id.fetch_add(fresh, Ordering::Relaxed);
+            assert!(first + fresh - 1 <= MAX_ENTITY_ID, "entity overflow");
+            entities.extend((first..first + fresh).map(|id| Entity::new(id as u32, 0)));
+        }
+        entities
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let Some(version) = self.entity_versions.get(entity.id() as usize) else { return true; };
+        entity.version() == *version
+    }
+
+    // for testing purposes
+    #[cfg(test)]
+    pub(crate) fn set_version(&mut self, id: u32, version: u32) {
+        if id as usize >= self.entity_versions.len() {
+            self.entity_versions.resize(id as usize + 1, 0);
+        }
+        self.entity_versions[id as usize] = version;
+    }
+
+    // for testing purposes
+    #[cfg(test)]
+    pub(crate) fn set_highest_id(&mut self, id: usize) {
+        *self.highest_free_entity_id.get_mut() = id;
+    }
+
+
+    // child can't become its own parent or the parent of one of its ancestors
+    fn check_reparent(&self, parent: Entity, child: Entity) -> Result<(), HierarchyError> {
+        if parent == child {
+            return Err(HierarchyError::self_parent(child));
+        }
+        if Ancestors::new(self, Some(parent)).any(|ancestor| ancestor == child) {
+            return Err(HierarchyError::cycle(parent, child));
+        }
+        Ok(())
+    }
+
+    /// Attaches child to parent, detaching it from its previous parent.
+    /// Fails if parent is the child itself or one of its descendants.
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) -> Result<(), HierarchyError> {
+        self.check_reparent(parent, child)?;
+        match self.parent(child) {
+            Some(old_parent) if old_parent == parent => return Ok(()),
+            Some(old_parent) => { self.detach_from_children_list(old_parent, child); },
+            None => (),
+        }
+        self.children.entry(parent.id() as usize).or_default().push(child);
+        self.parents.insert(child.id() as usize, parent);
+        Ok(())
+    }
+
+    /// Attaches child to parent at the given position among its siblings, index is clamped to the number of children.
+    /// Fails if parent is the child itself or one of its descendants.
+    pub fn insert_child_at(&mut self, parent: Entity, index: usize, child: Entity) -> Result<(), HierarchyError> {
+        self.check_reparent(parent, child)?;
+        if let Some(old_parent) = self.parent(child) {
+            self.detach_from_children_list(old_parent, child);
+        }
+        let children = self.children.entry(parent.id() as usize).or_default();
+        children.insert(index.min(children.len()), child);
+        self.parents.insert(child.id() as usize, parent);
+        Ok(())
+    }
+
+    /// Moves an existing child to the given position among its siblings, index is clamped to the number of children
+    pub fn move_child(&mut self, parent: Entity, child: Entity, index: usize) {
+        if self.parent(child) != Some(parent) { return; }
+        let Some(children) = self.children.get_mut(parent.id() as usize) else { return; };
+        let Some(old_index) = children.iter().position(|p| child == *p) else { return; };
+        children.remove(old_index);
+        children.insert(index.min(children.len()), child);
+    }
+
+    /// Replaces the order of parent's children, `children` must be a permutation of the current children
+    pub(crate) fn reorder_children(&mut self, parent: Entity, children: Vec<Entity>) {
+        let Some(current) = self.children.get_mut(parent.id() as usize) else { return; };
+        debug_assert_eq!(current.len(), children.len());
+        *current = children;
+    }
+
+    pub fn remove_parent(&mut self, child: Entity) {
+        if let Some(parent) = self.parents.remove(child.id() as usize) {
+            self.detach_from_children_list(parent, child);
+        }
+    }
+
+    pub fn remove_child(&mut self, parent: Entity, child: Entity) {
+        if self.parent(child) == Some(parent) {
+            self.remove_parent(child);
+        }
+    }
+
+    pub fn remove_children(&mut self, parent: Entity) {
+        let Some(children) = self.children.remove(parent.id() as usize) else { return; };
+        for child in children {
+            self.parents.remove(child.id() as usize);
+        }
+    }
+
+    // returns the position the child was at
+    fn detach_from_children_list(&mut self, parent: Entity, child: Entity) -> Option<usize> {
+        let children = self.children.get_mut(parent.id() as usize)?;
+        let index = children.iter().position(|p| child == *p)?;
+        children.remove(index);
+        Some(index)
+    }
+
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        if let Some(children) = self.children.get(entity.id() as usize) {
+            children
+        } else {
+            &[]
+        }
+    }
+
+    #[inline]
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.parents.get(entity.id() as usize).copied()
+    }
+}
+
+/// Iterates over the parent, grandparent, etc. of an entity, closest first
+pub struct Ancestors<'a> {
+    entities: &'a Entities,
+    current: Option<Entity>,
+}
+
+impl<'a> Ancestors<'a> {
+    pub(crate) fn new(entities: &'a Entities, entity: Option<Entity>) -> Self {
+        Self { entities, current: entity }
+    }
+}
+
+impl Iterator for Ancestors<'_> {
+    type Item = Entity;
+    fn next(&mut self) -> Option<Self::Item> {
+        let parent = self.entities.parent(self.current?);
+        self.current = parent;
+        parent
+    }
+}
+
+/// Iterates over the whole subtree of an entity in depth-first pre-order, excluding the entity itself
+pub struct DescendantsDepthFirst<'a> {
+    entities: &'a Entities,
+    stack: Vec<Entity>,
+}
+
+impl<'a> DescendantsDepthFirst<'a> {
+    pub(crate) fn new(entities: &'a Entities, entity: Option<Entity>) -> Self {
+        let stack = entity.map(|entity| entities.children(entity).iter().rev().copied().collect()).unwrap_or_default();
+        Self { entities, stack }
+    }
+}
+
+impl Iterator for DescendantsDepthFirst<'_> {
+    type Item = Entity;
+    fn next(&mut self) -> Option<Self::Item> {
+        let entity = self.stack.pop()?;
+        self.stack.extend(self.entities.children(entity).iter().rev());
+        Some(entity)
+    }
+}
+
+/// Iterates over the whole subtree of an entity level by level, excluding the entity itself
+pub struct DescendantsBreadthFirst<'a> {
+    entities: &'a Entities,
+    queue: VecDeque<Entity>,
+}
+
+impl<'a> DescendantsBreadthFirst<'a> {
+    pub(crate) fn new(entities: &'a Entities, entity: Option<Entity>) -> Self {
+        let queue = entity.map(|entity| entities.children(entity).iter().copied().collect()).unwrap_or_default();
+        Self { entities, queue }
+    }
+}
+
+impl Iterator for DescendantsBreadthFirst<'_> {
+    type Item = Entity;
+    fn next(&mut self) -> Option<Self::Item> {
+        let entity = self.queue.pop_front()?;
+        self.queue.extend(self.entities.children(entity));
+        Some(entity)
+    }
+}
@@ -0,0 +1,87 @@
+use std::{borrow::Borrow, fmt::{Debug, Display}, ops::Deref, sync::Arc};
+
+use crate::{Component, Entity, Immutable};
+
+/// Human readable name of an entity, always indexed so that [`crate::World::entities_named`] can find it.
+///
+/// Names are immutable, renaming an entity means inserting a new `Name`.
+///
+/// An [`Entity`] doesn't know which world it belongs to, so its `Debug` output stays `12v3`.
+/// Print [`crate::World::named`] instead to include the name, hierarchy errors include it on their own.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Name(Arc<str>);
+
+impl Name {
+    pub fn new(name: impl Into<Arc<str>>) -> Self {
+        Self(name.into())
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Component for Name {
+    type Mutability = Immutable;
+}
+
+impl Borrow<str> for Name {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Name {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Debug for Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for Name {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Name {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Entity printed together with its [`Name`], see [`crate::World::named`]
+#[derive(Clone, Copy)]
+pub struct NamedEntity<'a> {
+    entity: Entity,
+    name: Option<&'a Name>,
+}
+
+impl<'a> NamedEntity<'a> {
+    pub(crate) const fn new(entity: Entity, name: Option<&'a Name>) -> Self {
+        Self { entity, name }
+    }
+}
+
+/// Prints `{id}v{version}`, followed by the name if the entity has one
+impl Debug for NamedEntity<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name {
+            Some(name) => f.write_fmt(format_args!("{:?} {:?}", self.entity, name)),
+            None => Debug::fmt(&self.entity, f),
+        }
+    }
+}
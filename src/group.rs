@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use crate::{Entity, Signature, storage::sparse_set::SparseSet};
+
+/// Ids reached by adding or removing a single component, keyed by component id
+#[derive(Default)]
+pub struct Edges {
+    with: SparseSet<usize>,
+    without: SparseSet<usize>,
+}
+
+impl Edges {
+    #[inline]
+    pub fn with(&self, component_id: usize) -> Option<usize> {
+        self.with.get(component_id).copied()
+    }
+
+    #[inline]
+    pub fn without(&self, component_id: usize) -> Option<usize> {
+        self.without.get(component_id).copied()
+    }
+}
+
+/// Signatures stored by id, with the transitions between them cached in their edges
+pub(crate) trait Transitions {
+    fn signature(&self, id: usize) -> &Signature;
+
+    fn edges(&self, id: usize) -> &Edges;
+
+    fn edges_mut(&mut self, id: usize) -> &mut Edges;
+
+    /// Id of the signature, inserting it if it's missing
+    fn get_or_insert(&mut self, signature: Signature) -> usize;
+
+    /// Id of the signature with the component added, cached after the first lookup
+    #[inline]
+    fn with_component(&mut self, id: usize, component_id: usize) -> usize {
+        if let Some(target) = self.edges(id).with(component_id) {
+            return target;
+        }
+        let target = self.get_or_insert(self.signature(id).clone().with_set(component_id));
+        self.edges_mut(id).with.insert(component_id, target);
+        target
+    }
+
+    /// Id of the signature with the component removed, cached after the first lookup
+    #[inline]
+    fn without_component(&mut self, id: usize, component_id: usize) -> usize {
+        if let Some(target) = self.edges(id).without(component_id) {
+            return target;
+        }
+        let mut signature = self.signature(id).clone();
+        signature.unset(component_id);
+        let target = self.get_or_insert(signature);
+        self.edges_mut(id).without.insert(component_id, target);
+        target
+    }
+}
+
+struct Group {
+    signature: Signature,
+    entities: SparseSet<Entity>,
+    edges: Edges,
+}
+
+/// Entities grouped by their signature, caching the transitions between the groups
+#[derive(Default)]
+pub struct Groups {
+    groups: Vec<Group>,
+    group_ids: HashMap<Signature, usize>,
+}
+
+impl Groups {
+    pub fn iter(&self) -> impl Iterator<Item = (&Signature, &SparseSet<Entity>)> {
+        self.groups.iter().map(|group| (&group.signature, &group.entities))
+    }
+
+    #[inline]
+    pub fn get(&self, signature: &Signature) -> Option<&SparseSet<Entity>> {
+        self.get_group_id(signature).map(|group_id| &self.groups[group_id].entities)
+    }
+
+    #[inline]
+    pub(crate) fn get_group_id(&self, signature: &Signature) -> Option<usize> {
+        self.group_ids.get(signature).copied()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub(crate) fn group_id(&mut self, signature: &Signature) -> usize {
+        if let Some(group_id) = self.group_ids.get(signature) {
+            return *group_id;
+        }
+        let group_id = self.groups.len();
+        self.groups.push(Group {
+            signature: signature.clone(),
+            entities: SparseSet::new(),
+            edges: Edges::default(),
+        });
+        self.group_ids.insert(signature.clone(), group_id);
+        group_id
+    }
+
+    #[inline]
+    pub(crate) fn entities_mut(&mut self, group_id: usize) -> &mut SparseSet<Entity> {
+        &mut self.groups[group_id].entities
+    }
+}
+
+impl Transitions for Groups {
+    #[inline]
+    fn signature(&self, group_id: usize) -> &Signature {
+        &self.groups[group_id].signature
+    }
+
+    #[inline]
+    fn edges(&self, group_id: usize) -> &Edges {
+        &self.groups[group_id].edges
+    }
+
+    #[inline]
+    fn edges_mut(&mut self, group_id: usize) -> &mut Edges {
+        &mut self.groups[group_id].edges
+    }
+
+    #[inline]
+    fn get_or_insert(&mut self, signature: Signature) -> usize {
+        self.group_id(&signature)
+    }
+}
@@ -0,0 +1,187 @@
+use std::{any::Any, borrow::Borrow, collections::HashMap, hash::Hash, ops::Deref};
+
+use crate::{Component, ComponentId, ComponentMutability, Entity, World, access::{AccessBuilder, FilteredComponentAccess}, change_detection::{ComponentTicks, Tick}, component::Components, param::{SystemParam, SystemParamError}, storage::ptr::Ptr, system::SystemHandle, world::WorldPtr};
+
+/// Components that can be looked up by value, the index keeps a clone of every distinct value as its key
+pub trait IndexableComponent: Component + Hash + Eq + Clone {}
+impl<C: Component + Hash + Eq + Clone> IndexableComponent for C {}
+
+/// Entities grouped by the value of their component, registered through [`World::register_component_index`]
+///
+/// Values replaced or removed through the world are reindexed right away. Mutable components changed in place
+/// are reindexed before every batch of systems a schedule runs and by [`World::index`].
+pub struct ComponentIndex<C> {
+    entities: HashMap<C, Vec<Entity>>,
+    // change tick of the last refresh, values changed since then may still be filed under their previous value
+    refreshed: Tick,
+}
+
+impl<C: IndexableComponent> ComponentIndex<C> {
+    fn new() -> Self {
+        Self { entities: HashMap::new(), refreshed: Tick::default() }
+    }
+
+    /// Entities whose component equals value, in the order they were given it
+    #[inline]
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, value: &Q) -> &[Entity] where C: Borrow<Q> {
+        self.entities.get(value).map_or(&[], |entities| entities.as_slice())
+    }
+
+    #[inline]
+    pub fn first<Q: Hash + Eq + ?Sized>(&self, value: &Q) -> Option<Entity> where C: Borrow<Q> {
+        self.get(value).first().copied()
+    }
+
+    #[inline]
+    pub fn contains<Q: Hash + Eq + ?Sized>(&self, value: &Q) -> bool where C: Borrow<Q> {
+        self.entities.contains_key(value)
+    }
+
+    /// Distinct values of the component
+    pub fn iter(&self) -> impl Iterator<Item = (&C, &[Entity])> {
+        self.entities.iter().map(|(value, entities)| (value, entities.as_slice()))
+    }
+
+    /// Number of distinct values
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    // the entity must not be indexed yet
+    fn insert(&mut self, entity: Entity, value: &C) {
+        match self.entities.get_mut(value) {
+            Some(entities) => entities.push(entity),
+            None => { self.entities.insert(value.clone(), vec![entity]); },
+        }
+    }
+
+    // value is the entity's current value, which it may have been given since the last refresh
+    fn remove(&mut self, entity: Entity, value: &C) {
+        if let Some(entities) = self.entities.get_mut(value) && let Some(position) = entities.iter().position(|p| *p == entity) {
+            entities.remove(position);
+            if entities.is_empty() {
+                self.entities.remove(value);
+            }
+            return;
+        }
+        self.entities.retain(|_, entities| {
+            entities.retain(|p| *p != entity);
+            !entities.is_empty()
+        });
+    }
+
+    // moves the entities whose value changed since the last refresh under their current value
+    fn refresh<'a>(&mut self, change_tick: Tick, value: impl Fn(Entity) -> Option<(&'a C, &'a ComponentTicks)>) where C: 'a {
+        let refreshed = std::mem::replace(&mut self.refreshed, change_tick);
+        let mut moved = Vec::new();
+        self.entities.retain(|key, entities| {
+            entities.retain(|entity| match value(*entity) {
+                Some((value, ticks)) if (ticks.changed == refreshed || ticks.changed.is_newer_than(refreshed, change_tick)) && value != key => {
+                    moved.push((*entity, value));
+                    false
+                },
+                _ => true,
+            });
+            !entities.is_empty()
+        });
+        for (entity, value) in moved {
+            self.insert(entity, value);
+        }
+    }
+}
+
+type RefreshFn = unsafe fn(&mut (dyn Any + Send + Sync), &Components, ComponentId, Tick);
+
+// ComponentIndex with its type erased, updated by Components whenever the component is written or removed
+pub(crate) struct ErasedComponentIndex {
+    index: Box<dyn Any + Send + Sync>,
+    insert: unsafe fn(&mut (dyn Any + Send + Sync), Entity, Ptr<'_>),
+    remove: unsafe fn(&mut (dyn Any + Send + Sync), Entity, Ptr<'_>),
+    // only mutable components can change without going through the index
+    refresh: Option<RefreshFn>,
+}
+
+impl ErasedComponentIndex {
+    pub(crate) fn new<C: IndexableComponent>() -> Self {
+        Self {
+            index: Box::new(ComponentIndex::<C>::new()),
+            insert: |index, entity, value| unsafe { index.downcast_unchecked_mut::<ComponentIndex<C>>().insert(entity, value.cast_ref::<C>()) },
+            remove: |index, entity, value| unsafe { index.downcast_unchecked_mut::<ComponentIndex<C>>().remove(entity, value.cast_ref::<C>()) },
+            refresh: match C::Mutability::MUTABLE {
+                true => Some(|index, components, component_id, change_tick| unsafe {
+                    index.downcast_unchecked_mut::<ComponentIndex<C>>().refresh(change_tick, |entity| components.get_ptr_with_ticks_by_id(entity, component_id)
+                        .map(|(value, ticks)| (value.cast_ref::<C>(), ticks)));
+                }),
+                false => None,
+            },
+        }
+    }
+
+    /// # Safety
+    /// Value must point to the indexed component type, the entity must not be indexed yet
+    #[inline]
+    pub(crate) unsafe fn insert(&mut self, entity: Entity, value: Ptr<'_>) {
+        unsafe { (self.insert)(self.index.as_mut(), entity, value) }
+    }
+
+    /// # Safety
+    /// Value must point to the entity's current value of the indexed component type
+    #[inline]
+    pub(crate) unsafe fn remove(&mut self, entity: Entity, value: Ptr<'_>) {
+        unsafe { (self.remove)(self.index.as_mut(), entity, value) }
+    }
+
+    /// # Safety
+    /// Component_id must correspond to the indexed component type
+    #[inline]
+    pub(crate) unsafe fn refresh(&mut self, components: &Components, component_id: ComponentId, change_tick: Tick) {
+        if let Some(refresh) = self.refresh {
+            unsafe { refresh(self.index.as_mut(), components, component_id, change_tick) };
+        }
+    }
+
+    /// # Safety
+    /// C must be the indexed component type
+    #[inline]
+    pub(crate) unsafe fn get<C: IndexableComponent>(&self) -> &ComponentIndex<C> {
+        unsafe { self.index.downcast_unchecked_ref::<ComponentIndex<C>>() }
+    }
+}
+
+/// Read access to the [`ComponentIndex`] of C, registering the index if absent
+pub struct Index<'a, C: IndexableComponent> {
+    index: &'a ComponentIndex<C>,
+}
+
+impl<C: IndexableComponent> Deref for Index<'_, C> {
+    type Target = ComponentIndex<C>;
+    fn deref(&self) -> &Self::Target {
+        self.index
+    }
+}
+
+unsafe impl<C: IndexableComponent> SystemParam for Index<'_, C> {
+    type Item<'a> = Index<'a, C>;
+    type State = ComponentId;
+
+    fn join_access(world: &mut World, access: &mut AccessBuilder) -> Result<(), SystemParamError> {
+        let mut component_access = FilteredComponentAccess::default();
+        component_access.add_immutable(world.register_component::<C>().get()).map_err(SystemParamError::Conflict)?;
+        access.join_filtered_component_access(component_access).map_err(SystemParamError::Conflict)
+    }
+
+    fn init_state(world: &mut World, _: &SystemHandle) -> Result<Self::State, SystemParamError> {
+        Ok(world.register_component_index::<C>())
+    }
+
+    unsafe fn fetch<'a>(world_ptr: WorldPtr<'a>, state: &'a mut Self::State, _: &SystemHandle) -> Self::Item<'a> {
+        let index = unsafe { world_ptr.as_world().index_by_id::<C>(*state) };
+        Index { index }
+    }
+}
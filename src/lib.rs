@@ -1,5 +1,6 @@
-#![feature(sync_unsafe_cell, downcast_unchecked, allocator_api)]
-mod bitmap;
+#![feature(sync_unsafe_cell, downcast_unchecked, allocator_api, associated_type_defaults)]
+mod bitset;
+mod change_detection;
 mod component;
 mod entity;
 pub mod system;
@@ -11,20 +12,28 @@ pub mod schedule;
 mod trigger;
 mod event;
 mod observer;
+mod relationship;
+mod index;
+mod group;
 mod world;
 #[cfg(test)]
 mod tests;
 mod storage;
 pub mod error;
 
-pub use component::{ComponentId, Signature, ComponentBundle, Component};
-pub use world::{World, WorldResMut};
-pub use query::{Query, QueryData, Without, With, QueryFilter, Children};
+pub use component::{ComponentId, Signature, ComponentBundle, Component, ComponentHook, ComponentHooks, HookContext, RequiredComponents, Disabled, ComponentDescriptor, RawCloneFn, ComponentMutability, Mutable, Immutable, MutableComponent, StorageType};
+pub use world::{World, WorldResMut, EntityRef, EntityWorldMut};
+pub use query::{Query, QueryData, Without, With, QueryFilter, Children, Parent, IncludeDisabled, Added, DynamicQuery};
+pub use change_detection::{Tick, ComponentTicks, SystemTicks, Ref, Mut, CHECK_TICK_THRESHOLD, MAX_CHANGE_AGE};
 pub use resource::{Res, ResMut, ResourceId, Changed, Resource};
-pub use derive::{Component, Resource, ScheduleLabel};
+pub use derive::{Bundle, Component, Resource, ScheduleLabel};
 pub use schedule::{Schedule, ScheduleLabel};
-pub use system::{Commands, SystemHandle, SystemInput, SystemOutput, IntoSystem, SystemId, Local, System};
-pub use trigger::Trigger;
+pub use system::{Commands, EntityCommands, ChildSpawner, SystemHandle, SystemInput, SystemOutput, IntoSystem, SystemId, Local, System};
+pub use trigger::{Trigger, OnAdd, OnInsert, OnRemove, OnDespawn, OnDisable, OnEnable};
 pub use event::{EventReader, EventReadWriter, EventQueue, EventReaderState, EventIterator};
-pub use entity::Entity;
+pub use entity::{Entity, map::{EntityMap, MapEntities}, name::{Name, NamedEntity}, EntityRecyclingPolicy, HierarchyDespawnPolicy, Ancestors, DescendantsDepthFirst, DescendantsBreadthFirst, error::{HierarchyError, HierarchyErrorKind, EntityError, EntityErrorKind}};
 pub use observer::{ObserverInput, TriggerInput};
+pub use storage::ptr::{Ptr, PtrMut};
+pub use relationship::{Relationship, RelationDespawnPolicy};
+pub use index::{ComponentIndex, Index, IndexableComponent};
+pub use group::Groups;
@@ -1,10 +1,13 @@
-use crate::{Entity, IntoSystem, SystemId, World, access::AccessBuilder, param::{SystemParam, SystemParamError}, system::{Commands, System, SystemFunc, SystemHandle, SystemOutput, error::InternalSystemError}, trigger::Trigger, world::WorldPtr};
+use crate::{Entity, IntoSystem, SystemId, Tick, World, access::AccessBuilder, param::{SystemParam, SystemParamError}, system::{Commands, System, SystemFunc, SystemHandle, SystemOutput, error::InternalSystemError}, trigger::{OnDespawn, Trigger}, world::WorldPtr};
 use std::{any::TypeId, collections::HashMap, ptr::NonNull};
 
 #[derive(Default)]
 pub struct Observers {
     event_to_systems: HashMap<TypeId, Vec<NonNull<dyn System<Input = TriggerInput> + Send + Sync>>>,
     systems: Vec<Box<dyn System<Input = TriggerInput> + Send + Sync>>,
+    // bumped whenever the set of observed events changes, invalidates cached lookups
+    generation: usize,
+    despawn_observed: bool,
 }
 
 impl Observers {
@@ -14,6 +17,7 @@ impl Observers {
         let system_id = system.id().clone();
         self.event_to_systems.entry(event_type_id).or_default().push(NonNull::from(system.as_mut()));
         self.systems.push(system);
+        self.refresh();
         system_id
     }
 
@@ -22,9 +26,35 @@ impl Observers {
         let system_id = system.id().clone();
         self.event_to_systems.entry(event_type_id).or_default().push(NonNull::from(system.as_mut()));
         self.systems.push(system);
+        self.refresh();
         system_id
     }
 
+    #[inline]
+    pub(crate) fn is_observed(&self, event_type_id: &TypeId) -> bool {
+        self.event_to_systems.get(event_type_id).is_some_and(|systems| !systems.is_empty())
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    #[inline]
+    pub(crate) const fn generation(&self) -> usize {
+        self.generation
+    }
+
+    #[inline]
+    pub(crate) const fn despawn_observed(&self) -> bool {
+        self.despawn_observed
+    }
+
+    fn refresh(&mut self) {
+        self.generation += 1;
+        self.despawn_observed = self.is_observed(&TypeId::of::<OnDespawn>());
+    }
+
     pub(crate) fn trigger<E: Send + Sync + 'static>(&mut self, mut event: E, target: Option<Entity>, mut world_ptr: WorldPtr<'_>) -> Result<(), InternalSystemError> {
         let trigger_input = TriggerInput {
             event: NonNull::from(&mut event).cast::<()>(),
@@ -45,7 +75,14 @@ impl Observers {
         Ok(())
     }
 
+    pub(crate) fn check_change_ticks(&mut self, change_tick: Tick) {
+        for system in self.systems.iter_mut() {
+            system.check_change_tick(change_tick);
+        }
+    }
+
     pub(crate) fn remove_dead_observers(&mut self) {
+        let len = self.systems.len();
         let mut i = 0;
         while i < self.systems.len() {
             let id = self.systems[i].id();
@@ -64,6 +101,9 @@ impl Observers {
                 i += 1;
             }
         }
+        if self.systems.len() != len {
+            self.refresh();
+        }
     }
 }
 
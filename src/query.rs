@@ -1,15 +1,20 @@
-use crate::{ComponentBundle, ComponentId, Signature, access::{AccessBuilder, Conflict, FilteredComponentAccess}, bitmap::Bitmap, param::{SystemParam, SystemParamError}, system::SystemHandle, world::WorldPtr};
+use crate::{ComponentBundle, ComponentId, MutableComponent, Ptr, PtrMut, Signature, change_detection::{Mut, Ref, SystemTicks, Tick}, component::{Disabled, StorageType}, resource::Changed, access::{AccessBuilder, Conflict, FilteredComponentAccess}, param::{SystemParam, SystemParamError}, storage::table::Column, system::SystemHandle, world::WorldPtr};
 use super::{access::Access, Component, Entity, World};
-use std::{any::TypeId, collections::HashSet, marker::PhantomData, mem::MaybeUninit, ops::Deref};
+use std::{any::TypeId, borrow::Cow, collections::HashSet, iter::Enumerate, marker::PhantomData, mem::MaybeUninit, ops::Deref, ptr::NonNull, slice};
 
 const QUERY_MAX_VARIADIC_COUNT: usize = 32;
 
 pub struct Query<'a, D: QueryData, F: QueryFilter = ()> {
     _a: std::marker::PhantomData<(D, F)>,
     world_ptr: WorldPtr<'a>,
-    required: Bitmap,
-    forbidden: Bitmap,
+    required: Signature,
+    forbidden: Signature,
+    // Disabled's signature, empty when the query includes disabled entities
+    disabled: Signature,
     cached_component_ids: [ComponentId; QUERY_MAX_VARIADIC_COUNT],
+    filter_state: F::State,
+    ticks: SystemTicks,
+    matched: Cow<'a, MatchedTables>,
 }
 
 impl<'a, D: QueryData, F: QueryFilter> Query<'a, D, F> {
@@ -17,84 +22,56 @@ impl<'a, D: QueryData, F: QueryFilter> Query<'a, D, F> {
         let mut access = FilteredComponentAccess::default();
         D::join_filtered_component_access(world, &mut access)?;
         F::join_filtered_component_access(world, &mut access)?;
-        let required = *access.immutable() | *access.mutable() | *access.with();
-        let forbidden = *access.without();
+        let mut required = access.immutable() | access.mutable();
+        required |= access.with();
+        let forbidden = access.without().clone();
+        let disabled = world.register_component::<Disabled>().as_signature();
+        let disabled = if access.include_disabled() || required.intersects(&disabled) { Signature::new() } else { disabled };
         let cached_component_ids = D::cache_component_ids(world);
+        let filter_state = F::init_state(world);
+        let ticks = SystemTicks::new(Tick::default(), world.change_tick());
+        let matched = MatchedTables::new(world, &required, &(&forbidden | &disabled));
         Ok(Self {
             _a: std::marker::PhantomData,
             world_ptr: world.world_ptr_mut(),
             required,
             forbidden,
+            disabled,
             cached_component_ids,
+            filter_state,
+            ticks,
+            matched: Cow::Owned(matched),
         })
     }
 
     pub fn iter(&self) -> impl Iterator<Item = D::ItemRef<'a>> {
-        let required_signature = self.required;
-        let forbidden_signature = self.forbidden;
-        let world_ptr = self.world_ptr;
-        unsafe { world_ptr.as_world() }
-            .groups()
-            .iter()
-            .filter(move |(signature, _)| {
-                let signature = **signature;
-                (signature & required_signature == required_signature) &&
-                (signature & forbidden_signature).is_zero()
-            })
-            .flat_map(|(_, entities)| entities.iter().copied())
-            .map(|entity| unsafe { D::fetch_ref(self.world_ptr, entity, &self.cached_component_ids) })
+        QueryIter(QueryRows::new(self))
     }
 
     pub fn iter_mut(&mut self) -> impl Iterator<Item = D::ItemMut<'a>> {
-        let required_signature = self.required;
-        let forbidden_signature = self.forbidden;
-        unsafe { self.world_ptr.as_world() }
-            .groups()
-            .iter()
-            .filter(move |(signature, _)| {
-                let signature = **signature;
-                (signature & required_signature == required_signature) &&
-                (signature & forbidden_signature).is_zero()
-            })
-            .flat_map(|(_, entities)| entities.iter().copied())
-            .map(|entity| unsafe { D::fetch_mut(self.world_ptr, entity, &self.cached_component_ids) })
+        QueryIterMut(QueryRows::new(self))
     }
 
     /// # Safety
     /// can violate rust's reference rules
     pub unsafe fn iter_unsafe(&self) -> impl Iterator<Item = D::ItemMut<'a>> {
-        let required_signature = self.required;
-        let forbidden_signature = self.forbidden;
-        unsafe { self.world_ptr.as_world() }
-            .groups()
-            .iter()
-            .filter(move |(signature, _)| {
-                let signature = **signature;
-                (signature & required_signature == required_signature) &&
-                (signature & forbidden_signature).is_zero()
-            })
-            .flat_map(|(_, entities)| entities.iter().copied())
-            .map(|entity| unsafe { D::fetch_mut(self.world_ptr, entity, &self.cached_component_ids) })
+        QueryIterMut(QueryRows::new(self))
     }
 
     pub fn get(&self, entity: Entity) -> Option<D::ItemRef<'_>> {
         let entity_signature = unsafe { self.world_ptr.as_world() }.get_entity_signature(entity)?;
-        if ((entity_signature & self.required) != self.required) ||
-        !(entity_signature & self.forbidden).is_zero()
-        {
+        if !self.matches(&entity_signature) || !self.filter(entity) {
             return None;
         }
-        Some(unsafe { D::fetch_ref(self.world_ptr, entity, &self.cached_component_ids) })
+        Some(unsafe { D::fetch_ref(self.world_ptr, entity, &self.cached_component_ids, self.ticks) })
     }
 
     pub fn get_mut(&mut self, entity: Entity) -> Option<D::ItemMut<'_>> {
         let entity_signature = unsafe { self.world_ptr.as_world() }.get_entity_signature(entity)?;
-        if ((entity_signature & self.required) != self.required) ||
-        !(entity_signature & self.forbidden).is_zero()
-        {
+        if !self.matches(&entity_signature) || !self.filter(entity) {
             return None;
         }
-        Some(unsafe { D::fetch_mut(self.world_ptr, entity, &self.cached_component_ids) })
+        Some(unsafe { D::fetch_mut(self.world_ptr, entity, &self.cached_component_ids, self.ticks) })
     }
 
     /// # Safety
@@ -102,21 +79,31 @@ impl<'a, D: QueryData, F: QueryFilter> Query<'a, D, F> {
     /// Might violate rust's reference rules
     pub unsafe fn get_unsafe(&self, entity: Entity) -> Option<D::ItemMut<'_>> {
         let entity_signature = unsafe { self.world_ptr.as_world() }.get_entity_signature(entity)?;
-        if ((entity_signature & self.required) != self.required) ||
-        !(entity_signature & self.forbidden).is_zero()
-        {
+        if !self.matches(&entity_signature) || !self.filter(entity) {
             return None;
         }
-        Some(unsafe { D::fetch_mut(self.world_ptr, entity, &self.cached_component_ids) })
+        Some(unsafe { D::fetch_mut(self.world_ptr, entity, &self.cached_component_ids, self.ticks) })
+    }
+
+    #[inline]
+    fn matches(&self, signature: &Signature) -> bool {
+        self.required.is_subset(signature) &&
+        signature.is_disjoint(&self.forbidden) &&
+        signature.is_disjoint(&self.disabled)
+    }
+
+    #[inline]
+    fn filter(&self, entity: Entity) -> bool {
+        unsafe { F::filter(self.world_ptr, entity, &self.filter_state, self.ticks) }
     }
 
     // for testing purposes
-    pub(crate) fn required(&self) -> &Bitmap {
+    pub(crate) fn required(&self) -> &Signature {
         &self.required
     }
 
     // for testing purposes
-    pub(crate) fn forbidden(&self) -> &Bitmap {
+    pub(crate) fn forbidden(&self) -> &Signature {
         &self.forbidden
     }
 
@@ -131,7 +118,7 @@ impl<'a, D: QueryData, F: QueryFilter> Query<'a, D, F> {
 
 unsafe impl<D: QueryData, F: QueryFilter> SystemParam for Query<'_, D, F> {
     type Item<'a> = Query<'a, D, F>;
-    type State = (Signature, [ComponentId; QUERY_MAX_VARIADIC_COUNT], Signature);
+    type State = (Signature, [ComponentId; QUERY_MAX_VARIADIC_COUNT], Signature, Signature, F::State, MatchedTables);
 
     fn join_access(world: &mut World, access: &mut AccessBuilder) -> Result<(), SystemParamError> {
         let mut filtered_component_access = FilteredComponentAccess::default();
@@ -142,25 +129,360 @@ unsafe impl<D: QueryData, F: QueryFilter> SystemParam for Query<'_, D, F> {
 
     fn init_state(world: &mut World, _: &SystemHandle) -> Result<Self::State, SystemParamError> {
         let query = Query::<D, F>::new(world).map_err(SystemParamError::Conflict)?;
-        Ok((query.required, query.cached_component_ids, query.forbidden))
+        Ok((query.required, query.cached_component_ids, query.forbidden, query.disabled, query.filter_state, query.matched.into_owned()))
     }
 
-    unsafe fn fetch<'a>(world_ptr: WorldPtr<'a>, state: &'a mut Self::State, _: &SystemHandle) -> Self::Item<'a> {
+    unsafe fn fetch<'a>(world_ptr: WorldPtr<'a>, state: &'a mut Self::State, system_handle: &SystemHandle) -> Self::Item<'a> {
+        let (required, cached_component_ids, forbidden, disabled, filter_state, matched) = state;
+        matched.update(unsafe { world_ptr.as_world() });
         Query {
             _a: Default::default(),
-            cached_component_ids: state.1,
-            required: state.0,
-            forbidden: state.2,
+            cached_component_ids: *cached_component_ids,
+            required: required.clone(),
+            forbidden: forbidden.clone(),
+            disabled: disabled.clone(),
+            filter_state: filter_state.clone(),
+            ticks: system_handle.ticks(),
             world_ptr,
+            matched: Cow::Borrowed(matched),
+        }
+    }
+}
+
+/// Tables holding a query's matches, extended with the tables created since the last update. Every row of a matched
+/// table has the query's table components, the sparse set ones are checked for each entity
+#[derive(Clone)]
+pub struct MatchedTables {
+    table_ids: Vec<usize>,
+    // number of the world's tables already checked, tables are never removed
+    checked: usize,
+    required: Signature,
+    forbidden: Signature,
+    sparse: SparseMatch,
+}
+
+impl MatchedTables {
+    fn new(world: &World, required: &Signature, forbidden: &Signature) -> Self {
+        let table_components = world.table_components();
+        let mut matched = Self {
+            table_ids: Vec::new(),
+            checked: 0,
+            required: required & table_components,
+            forbidden: forbidden & table_components,
+            sparse: SparseMatch {
+                required: required.iter_ones().filter(|component_id| !table_components.get(*component_id)).collect(),
+                forbidden: forbidden.iter_ones().filter(|component_id| !table_components.get(*component_id)).collect(),
+            },
+        };
+        matched.update(world);
+        matched
+    }
+
+    fn update(&mut self, world: &World) {
+        for (table_id, table) in world.tables().iter().enumerate().skip(self.checked) {
+            if self.required.is_subset(table.signature()) && table.signature().is_disjoint(&self.forbidden) {
+                self.table_ids.push(table_id);
+            }
+        }
+        self.checked = world.tables().len();
+    }
+}
+
+// sparse set components a query requires or forbids
+#[derive(Clone)]
+struct SparseMatch {
+    required: Vec<usize>,
+    forbidden: Vec<usize>,
+}
+
+impl SparseMatch {
+    #[inline(always)]
+    fn matches(&self, world: &World, entity: Entity) -> bool {
+        self.required.iter().all(|component_id| world.sparse_contains(entity, *component_id)) &&
+        !self.forbidden.iter().any(|component_id| world.sparse_contains(entity, *component_id))
+    }
+}
+
+// walks the rows of every matched table, the query's columns are looked up once per table
+struct QueryRows<'a, 's, D: QueryData, F: QueryFilter> {
+    query: &'s Query<'a, D, F>,
+    world: &'a World,
+    tables: slice::Iter<'s, usize>,
+    // none when the query has no sparse set components to check
+    sparse: Option<&'s SparseMatch>,
+    entities: Enumerate<slice::Iter<'a, Entity>>,
+    fetch: Option<D::Fetch<'a>>,
+    filter_fetch: Option<F::Fetch<'a>>,
+}
+
+impl<'a, 's, D: QueryData, F: QueryFilter> QueryRows<'a, 's, D, F> {
+    fn new(query: &'s Query<'a, D, F>) -> Self {
+        let sparse = &query.matched.sparse;
+        Self {
+            query,
+            world: unsafe { query.world_ptr.as_world() },
+            tables: query.matched.table_ids.iter(),
+            sparse: (!sparse.required.is_empty() || !sparse.forbidden.is_empty()).then_some(sparse),
+            entities: [].iter().enumerate(),
+            fetch: None,
+            filter_fetch: None,
+        }
+    }
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<(&D::Fetch<'a>, usize, Entity)> {
+        let (world, world_ptr, filter_state, ticks, sparse) = (self.world, self.query.world_ptr, &self.query.filter_state, self.query.ticks, self.sparse);
+        loop {
+            if let Some(filter_fetch) = &self.filter_fetch {
+                for (row, &entity) in self.entities.by_ref() {
+                    if sparse.is_none_or(|sparse| sparse.matches(world, entity)) && unsafe { F::filter_row(filter_fetch, world_ptr, entity, row, filter_state, ticks) } {
+                        return Some((self.fetch.as_ref()?, row, entity));
+                    }
+                }
+            }
+            self.next_table()?;
+        }
+    }
+
+    // internal iteration keeps the table's fetch and rows in registers, unlike repeated calls to next
+    #[inline]
+    fn fold<B>(mut self, mut acc: B, mut f: impl FnMut(B, &D::Fetch<'a>, usize, Entity) -> B) -> B {
+        let (world, world_ptr, filter_state, ticks, sparse) = (self.world, self.query.world_ptr, &self.query.filter_state, self.query.ticks, self.sparse);
+        loop {
+            if let (Some(fetch), Some(filter_fetch)) = (&self.fetch, &self.filter_fetch) {
+                for (row, &entity) in std::mem::replace(&mut self.entities, [].iter().enumerate()) {
+                    if sparse.is_none_or(|sparse| sparse.matches(world, entity)) && unsafe { F::filter_row(filter_fetch, world_ptr, entity, row, filter_state, ticks) } {
+                        acc = f(acc, fetch, row, entity);
+                    }
+                }
+            }
+            if self.next_table().is_none() {
+                return acc;
+            }
         }
     }
+
+    // kept out of line so the per-row path stays small enough to inline
+    #[inline(never)]
+    fn next_table(&mut self) -> Option<()> {
+        let table_id = *self.tables.next()?;
+        self.fetch = Some(unsafe { D::init_fetch(self.query.world_ptr, table_id, &self.query.cached_component_ids) });
+        self.filter_fetch = Some(unsafe { F::init_fetch(self.query.world_ptr, table_id, &self.query.filter_state) });
+        self.entities = self.world.tables()[table_id].entities().iter().enumerate();
+        Some(())
+    }
+}
+
+struct QueryIter<'a, 's, D: QueryData, F: QueryFilter>(QueryRows<'a, 's, D, F>);
+
+impl<'a, D: QueryData, F: QueryFilter> Iterator for QueryIter<'a, '_, D, F> {
+    type Item = D::ItemRef<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (world_ptr, component_ids, ticks) = (self.0.query.world_ptr, &self.0.query.cached_component_ids, self.0.query.ticks);
+        let (fetch, row, entity) = self.0.next()?;
+        Some(unsafe { D::fetch_row_ref(fetch, world_ptr, entity, row, component_ids, ticks) })
+    }
+
+    #[inline]
+    fn fold<B, G: FnMut(B, Self::Item) -> B>(self, init: B, mut f: G) -> B {
+        let (world_ptr, component_ids, ticks) = (self.0.query.world_ptr, &self.0.query.cached_component_ids, self.0.query.ticks);
+        self.0.fold(init, |acc, fetch, row, entity| f(acc, unsafe { D::fetch_row_ref(fetch, world_ptr, entity, row, component_ids, ticks) }))
+    }
+}
+
+struct QueryIterMut<'a, 's, D: QueryData, F: QueryFilter>(QueryRows<'a, 's, D, F>);
+
+impl<'a, D: QueryData, F: QueryFilter> Iterator for QueryIterMut<'a, '_, D, F> {
+    type Item = D::ItemMut<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (world_ptr, component_ids, ticks) = (self.0.query.world_ptr, &self.0.query.cached_component_ids, self.0.query.ticks);
+        let (fetch, row, entity) = self.0.next()?;
+        Some(unsafe { D::fetch_row_mut(fetch, world_ptr, entity, row, component_ids, ticks) })
+    }
+
+    #[inline]
+    fn fold<B, G: FnMut(B, Self::Item) -> B>(self, init: B, mut f: G) -> B {
+        let (world_ptr, component_ids, ticks) = (self.0.query.world_ptr, &self.0.query.cached_component_ids, self.0.query.ticks);
+        self.0.fold(init, |acc, fetch, row, entity| f(acc, unsafe { D::fetch_row_mut(fetch, world_ptr, entity, row, component_ids, ticks) }))
+    }
+}
+
+/// Query over components known only by their ids, yields their pointers in the order the ids were given
+///
+/// It borrows the whole [`World`] mutably and is not a [`crate::param::SystemParam`], so systems can't declare one
+/// and it can only be used from exclusive world access, outside of schedules.
+pub struct DynamicQuery<'a> {
+    world: &'a mut World,
+    component_ids: Vec<ComponentId>,
+    required: Signature,
+    forbidden: Signature,
+    include_disabled: bool,
+}
+
+impl<'a> DynamicQuery<'a> {
+    pub fn new(world: &'a mut World, component_ids: &[ComponentId]) -> Self {
+        let mut required = Signature::new();
+        for component_id in component_ids {
+            assert!(world.component_name(*component_id).is_some(), "component id {} not registered", component_id.get());
+            assert!(!required.get(component_id.get()), "component id {} fetched more than once", component_id.get());
+            required.set(component_id.get());
+        }
+        world.register_component::<Disabled>();
+        Self {
+            world,
+            component_ids: component_ids.to_vec(),
+            required,
+            forbidden: Signature::new(),
+            include_disabled: false,
+        }
+    }
+
+    /// Only matches entities that also have these components, without fetching them
+    pub fn with(mut self, component_ids: &[ComponentId]) -> Self {
+        for component_id in component_ids {
+            self.required.set(component_id.get());
+        }
+        self
+    }
+
+    pub fn without(mut self, component_ids: &[ComponentId]) -> Self {
+        for component_id in component_ids {
+            self.forbidden.set(component_id.get());
+        }
+        self
+    }
+
+    pub fn include_disabled(mut self) -> Self {
+        self.include_disabled = true;
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Vec<Ptr<'_>>)> {
+        let world = &*self.world;
+        self.rows(world).flat_map(move |(table_id, rows)| {
+            let table = &world.tables()[table_id];
+            let columns: Vec<Option<&Column>> = self.component_ids.iter().map(|component_id| table.column(component_id.get())).collect();
+            rows.map(move |(row, entity)| {
+                let ptrs = self.component_ids.iter().zip(columns.iter())
+                    .map(|(component_id, column)| match column {
+                        Some(column) => column.get(row),
+                        None => world.get_by_id(entity, *component_id).expect("DynamicQuery component not found"),
+                    })
+                    .collect();
+                (entity, ptrs)
+            })
+        })
+    }
+
+    /// Marks the fetched components changed
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, Vec<PtrMut<'_>>)> {
+        let mut world_ptr = self.world.world_ptr_mut();
+        let query = &*self;
+        let tick = query.world.change_tick();
+        query.rows(unsafe { world_ptr.as_world() }).flat_map(move |(table_id, rows)| {
+            let table = &mut unsafe { world_ptr.as_world_mut() }.tables_mut()[table_id];
+            let columns: Vec<Option<NonNull<Column>>> = query.component_ids.iter()
+                .map(|component_id| table.column_mut(component_id.get()).map(NonNull::from))
+                .collect();
+            for (component_id, column) in query.component_ids.iter().zip(columns.iter()) {
+                if column.is_some() { query.world.assert_mutable(*component_id); }
+            }
+            rows.map(move |(row, entity)| {
+                let ptrs = query.component_ids.iter().zip(columns.iter())
+                    .map(|(component_id, column)| match column {
+                        Some(column) => {
+                            let (ptr, ticks) = unsafe { &mut *column.as_ptr() }.get_mut_with_ticks(row);
+                            ticks.changed = tick;
+                            ptr
+                        },
+                        None => unsafe { world_ptr.as_world_mut() }.get_by_id_mut(entity, *component_id).expect("DynamicQuery component not found"),
+                    })
+                    .collect();
+                (entity, ptrs)
+            })
+        })
+    }
+
+    // matching entities and their rows in each table they can be in
+    fn rows(&self, world: &World) -> impl Iterator<Item = (usize, impl Iterator<Item = (usize, Entity)>)> {
+        let mut forbidden = self.forbidden.clone();
+        let disabled = world.get_component_id::<Disabled>().expect("DynamicQuery Disabled not registered").get();
+        if !self.include_disabled && !self.required.get(disabled) {
+            forbidden.set(disabled);
+        }
+        let MatchedTables { table_ids, sparse, .. } = MatchedTables::new(world, &self.required, &forbidden);
+        table_ids.into_iter().map(move |table_id| {
+            let sparse = sparse.clone();
+            let rows = world.tables()[table_id].entities().iter().copied().enumerate().filter(move |(_, entity)| sparse.matches(world, *entity));
+            (table_id, rows)
+        })
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<Vec<Ptr<'_>>> {
+        let entity_signature = self.world.get_entity_signature(entity)?;
+        self.matches(&entity_signature).then(|| self.fetch_ref(entity))
+    }
+
+    /// Marks the fetched components changed
+    pub fn get_mut(&mut self, entity: Entity) -> Option<Vec<PtrMut<'_>>> {
+        let entity_signature = self.world.get_entity_signature(entity)?;
+        if !self.matches(&entity_signature) { return None; }
+        Some(unsafe { Self::fetch_mut(self.world.world_ptr_mut(), &self.component_ids, entity) })
+    }
+
+    #[inline]
+    fn matches(&self, signature: &Signature) -> bool {
+        let disabled = self.world.get_component_id::<Disabled>().expect("DynamicQuery Disabled not registered").get();
+        self.required.is_subset(signature) &&
+        signature.is_disjoint(&self.forbidden) &&
+        (self.include_disabled || self.required.get(disabled) || !signature.get(disabled))
+    }
+
+    fn fetch_ref(&self, entity: Entity) -> Vec<Ptr<'_>> {
+        self.component_ids.iter()
+            .map(|component_id| self.world.get_by_id(entity, *component_id).expect("DynamicQuery component not found"))
+            .collect()
+    }
+
+    // component ids are unique so the pointers never alias
+    unsafe fn fetch_mut<'b>(mut world_ptr: WorldPtr<'b>, component_ids: &[ComponentId], entity: Entity) -> Vec<PtrMut<'b>> {
+        component_ids.iter()
+            .map(|component_id| unsafe { world_ptr.as_world_mut() }.get_by_id_mut(entity, *component_id).expect("DynamicQuery component not found"))
+            .collect()
+    }
+}
+
+// column of a table component in the table, sparse set components and components the table lacks have none
+#[inline]
+unsafe fn table_column(world_ptr: WorldPtr<'_>, table_id: usize, component_id: ComponentId) -> Option<&Column> {
+    unsafe { world_ptr.as_world() }.tables()[table_id].column(component_id.get())
+}
+
+#[inline]
+unsafe fn table_column_mut(mut world_ptr: WorldPtr<'_>, table_id: usize, component_id: ComponentId) -> Option<NonNull<Column>> {
+    unsafe { world_ptr.as_world_mut() }.tables_mut()[table_id].column_mut(component_id.get()).map(NonNull::from)
 }
 
 pub trait QueryItem: Send + Sync {
     type ItemRef<'a>;
     type ItemMut<'a>;
-    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId) -> Self::ItemRef<'_>;
-    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId) -> Self::ItemMut<'_>;
+    /// What the item fetches from a table, resolved once before iterating its rows
+    type Fetch<'a>;
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'_>;
+    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'_>;
+    /// # Safety
+    /// Table_id must belong to the world
+    unsafe fn init_fetch(world_ptr: WorldPtr<'_>, table_id: usize, component_index: ComponentId) -> Self::Fetch<'_>;
+    /// # Safety
+    /// Entity must be at the row of the table the fetch was initialized with
+    unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a>;
+    /// # Safety
+    /// Entity must be at the row of the table the fetch was initialized with
+    unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a>;
     fn component_id_or_init(world: &mut World) -> ComponentId;
     fn component_id(_: &World) -> ComponentId;
     fn join_filtered_component_access(_: &mut World, _: &mut FilteredComponentAccess) -> Result<(), Conflict> { Ok(()) }
@@ -169,16 +491,35 @@ pub trait QueryItem: Send + Sync {
 impl<C: Component> QueryItem for &C {
     type ItemRef<'a> = &'a C;
     type ItemMut<'a> = &'a C;
+    type Fetch<'a> = Option<&'a Column>;
     #[inline]
-    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_id: ComponentId) -> Self::ItemRef<'_> {
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_id: ComponentId, _: SystemTicks) -> Self::ItemRef<'_> {
         unsafe { world_ptr.as_world().get_component_by_id_unchecked::<C>(entity, component_id) }
     }
 
     #[inline]
-    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, component_id: ComponentId) -> Self::ItemMut<'_> {
+    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, component_id: ComponentId, _: SystemTicks) -> Self::ItemMut<'_> {
         unsafe { world_ptr.as_world().get_component_by_id_unchecked::<C>(entity, component_id) }
     }
 
+    #[inline]
+    unsafe fn init_fetch(world_ptr: WorldPtr<'_>, table_id: usize, component_id: ComponentId) -> Self::Fetch<'_> {
+        unsafe { table_column(world_ptr, table_id, component_id) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_id: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Self as QueryItem>::fetch_ref(world_ptr, entity, component_id, ticks) };
+        }
+        unsafe { fetch.expect("QueryItem column not found").get(row).cast_ref::<C>() }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_id: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a> {
+        unsafe { <Self as QueryItem>::fetch_row_ref(fetch, world_ptr, entity, row, component_id, ticks) }
+    }
+
     fn component_id_or_init(world: &mut World) -> ComponentId {
         world.register_component::<C>()
     }
@@ -193,17 +534,44 @@ impl<C: Component> QueryItem for &C {
     }
 }
 
-impl<C: Component> QueryItem for &mut C {
+impl<C: MutableComponent> QueryItem for &mut C {
     type ItemRef<'a> = &'a C;
     type ItemMut<'a> = &'a mut C;
+    type Fetch<'a> = Option<NonNull<Column>>;
     #[inline]
-    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId) -> Self::ItemRef<'_> {
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, _: SystemTicks) -> Self::ItemRef<'_> {
         unsafe { world_ptr.as_world().get_component_by_id_unchecked::<C>(entity, component_index) }
     }
 
     #[inline]
-    unsafe fn fetch_mut(mut world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId) -> Self::ItemMut<'_> {
-        unsafe { world_ptr.as_world_mut().get_component_by_id_unchecked_mut::<C>(entity, component_index) }
+    unsafe fn fetch_mut(mut world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'_> {
+        let (component, component_ticks) = unsafe { world_ptr.as_world_mut().get_component_with_ticks_by_id_mut::<C>(entity, component_index) }
+            .expect("QueryItem component not found");
+        component_ticks.changed = ticks.this_run();
+        component
+    }
+
+    #[inline]
+    unsafe fn init_fetch(world_ptr: WorldPtr<'_>, table_id: usize, component_index: ComponentId) -> Self::Fetch<'_> {
+        unsafe { table_column_mut(world_ptr, table_id, component_index) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Self as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) };
+        }
+        unsafe { fetch.expect("QueryItem column not found").as_ref().get(row).cast_ref::<C>() }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Self as QueryItem>::fetch_mut(world_ptr, entity, component_index, ticks) };
+        }
+        let (mut component, component_ticks) = unsafe { fetch.expect("QueryItem column not found").as_mut() }.get_mut_with_ticks(row);
+        component_ticks.changed = ticks.this_run();
+        unsafe { component.cast_mut::<C>() }
     }
 
     fn component_id_or_init(world: &mut World) -> ComponentId {
@@ -223,14 +591,28 @@ impl<C: Component> QueryItem for &mut C {
 impl QueryItem for Entity {
     type ItemRef<'a> = Entity;
     type ItemMut<'a> = Entity;
+    type Fetch<'a> = ();
+
+    #[inline]
+    unsafe fn fetch_ref(_: WorldPtr<'_>, entity: Entity, _: ComponentId, _: SystemTicks) -> Self::ItemRef<'_> {
+        entity
+    }
 
     #[inline]
-    unsafe fn fetch_ref(_: WorldPtr<'_>, entity: Entity, _: ComponentId) -> Self::ItemRef<'_> {
+    unsafe fn fetch_mut(_: WorldPtr<'_>, entity: Entity, _: ComponentId, _: SystemTicks) -> Self::ItemMut<'_> {
         entity
     }
 
     #[inline]
-    unsafe fn fetch_mut(_: WorldPtr<'_>, entity: Entity, _: ComponentId) -> Self::ItemMut<'_> {
+    unsafe fn init_fetch(_: WorldPtr<'_>, _: usize, _: ComponentId) -> Self::Fetch<'_> {}
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(_: &Self::Fetch<'a>, _: WorldPtr<'a>, entity: Entity, _: usize, _: ComponentId, _: SystemTicks) -> Self::ItemRef<'a> {
+        entity
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(_: &Self::Fetch<'a>, _: WorldPtr<'a>, entity: Entity, _: usize, _: ComponentId, _: SystemTicks) -> Self::ItemMut<'a> {
         entity
     }
 
@@ -246,16 +628,35 @@ impl QueryItem for Entity {
 impl<C: Component> QueryItem for Option<&C> {
     type ItemRef<'a> = Option<&'a C>;
     type ItemMut<'a> = Option<&'a C>;
+    type Fetch<'a> = Option<&'a Column>;
     #[inline]
-    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId) -> Self::ItemRef<'_> {
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, _: SystemTicks) -> Self::ItemRef<'_> {
         unsafe { world_ptr.as_world().get_component_by_id::<C>(entity, component_index) }
     }
 
     #[inline]
-    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId) -> Self::ItemMut<'_> {
+    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, _: SystemTicks) -> Self::ItemMut<'_> {
         unsafe { world_ptr.as_world().get_component_by_id::<C>(entity, component_index) }
     }
 
+    #[inline]
+    unsafe fn init_fetch(world_ptr: WorldPtr<'_>, table_id: usize, component_index: ComponentId) -> Self::Fetch<'_> {
+        unsafe { table_column(world_ptr, table_id, component_index) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Self as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) };
+        }
+        fetch.map(|column| unsafe { column.get(row).cast_ref::<C>() })
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a> {
+        unsafe { <Self as QueryItem>::fetch_row_ref(fetch, world_ptr, entity, row, component_index, ticks) }
+    }
+
     fn component_id_or_init(world: &mut World) -> ComponentId {
         world.register_component::<C>()
     }
@@ -271,17 +672,47 @@ impl<C: Component> QueryItem for Option<&C> {
     }
 }
 
-impl<C: Component> QueryItem for Option<&mut C> {
+impl<C: MutableComponent> QueryItem for Option<&mut C> {
     type ItemRef<'a> = Option<&'a C>;
     type ItemMut<'a> = Option<&'a mut C>;
+    type Fetch<'a> = Option<NonNull<Column>>;
     #[inline]
-    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId) -> Self::ItemRef<'_> {
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, _: SystemTicks) -> Self::ItemRef<'_> {
         unsafe { world_ptr.as_world().get_component_by_id::<C>(entity, component_index) }
     }
 
     #[inline]
-    unsafe fn fetch_mut(mut world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId) -> Self::ItemMut<'_> {
-        unsafe { world_ptr.as_world_mut().get_component_by_id_mut::<C>(entity, component_index) }
+    unsafe fn fetch_mut(mut world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'_> {
+        unsafe { world_ptr.as_world_mut().get_component_with_ticks_by_id_mut::<C>(entity, component_index) }
+            .map(|(component, component_ticks)| {
+                component_ticks.changed = ticks.this_run();
+                component
+            })
+    }
+
+    #[inline]
+    unsafe fn init_fetch(world_ptr: WorldPtr<'_>, table_id: usize, component_index: ComponentId) -> Self::Fetch<'_> {
+        unsafe { table_column_mut(world_ptr, table_id, component_index) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Self as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) };
+        }
+        fetch.map(|column| unsafe { column.as_ref().get(row).cast_ref::<C>() })
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Self as QueryItem>::fetch_mut(world_ptr, entity, component_index, ticks) };
+        }
+        fetch.map(|mut column| {
+            let (mut component, component_ticks) = unsafe { column.as_mut() }.get_mut_with_ticks(row);
+            component_ticks.changed = ticks.this_run();
+            unsafe { component.cast_mut::<C>() }
+        })
     }
 
     fn component_id_or_init(world: &mut World) -> ComponentId {
@@ -299,6 +730,108 @@ impl<C: Component> QueryItem for Option<&mut C> {
     }
 }
 
+impl<C: Component> QueryItem for Ref<'_, C> {
+    type ItemRef<'a> = Ref<'a, C>;
+    type ItemMut<'a> = Ref<'a, C>;
+    type Fetch<'a> = Option<&'a Column>;
+    #[inline]
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'_> {
+        let (component, component_ticks) = unsafe { world_ptr.as_world().get_component_with_ticks_by_id::<C>(entity, component_index) }
+            .expect("QueryItem component not found");
+        Ref::new(component, component_ticks, ticks)
+    }
+
+    #[inline]
+    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'_> {
+        unsafe { <Self as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) }
+    }
+
+    #[inline]
+    unsafe fn init_fetch(world_ptr: WorldPtr<'_>, table_id: usize, component_index: ComponentId) -> Self::Fetch<'_> {
+        unsafe { table_column(world_ptr, table_id, component_index) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Self as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) };
+        }
+        let column = fetch.expect("QueryItem column not found");
+        Ref::new(unsafe { column.get(row).cast_ref::<C>() }, column.get_ticks(row), ticks)
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a> {
+        unsafe { <Self as QueryItem>::fetch_row_ref(fetch, world_ptr, entity, row, component_index, ticks) }
+    }
+
+    fn component_id_or_init(world: &mut World) -> ComponentId {
+        world.register_component::<C>()
+    }
+
+    #[inline]
+    fn component_id(world: &World) -> ComponentId {
+        world.get_component_id::<C>().expect("QueryItem component index not found")
+    }
+
+    fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
+        access.add_immutable(world.register_component::<C>().get())
+    }
+}
+
+impl<C: MutableComponent> QueryItem for Mut<'_, C> {
+    type ItemRef<'a> = Ref<'a, C>;
+    type ItemMut<'a> = Mut<'a, C>;
+    type Fetch<'a> = Option<NonNull<Column>>;
+    #[inline]
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'_> {
+        unsafe { <Ref<C> as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) }
+    }
+
+    #[inline]
+    unsafe fn fetch_mut(mut world_ptr: WorldPtr<'_>, entity: Entity, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'_> {
+        let (component, component_ticks) = unsafe { world_ptr.as_world_mut().get_component_with_ticks_by_id_mut::<C>(entity, component_index) }
+            .expect("QueryItem component not found");
+        Mut::new(component, component_ticks, ticks)
+    }
+
+    #[inline]
+    unsafe fn init_fetch(world_ptr: WorldPtr<'_>, table_id: usize, component_index: ComponentId) -> Self::Fetch<'_> {
+        unsafe { table_column_mut(world_ptr, table_id, component_index) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Ref<C> as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) };
+        }
+        let column = unsafe { fetch.expect("QueryItem column not found").as_ref() };
+        Ref::new(unsafe { column.get(row).cast_ref::<C>() }, column.get_ticks(row), ticks)
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a> {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { <Self as QueryItem>::fetch_mut(world_ptr, entity, component_index, ticks) };
+        }
+        let (mut component, component_ticks) = unsafe { fetch.expect("QueryItem column not found").as_mut() }.get_mut_with_ticks(row);
+        Mut::new(unsafe { component.cast_mut::<C>() }, component_ticks, ticks)
+    }
+
+    fn component_id_or_init(world: &mut World) -> ComponentId {
+        world.register_component::<C>()
+    }
+
+    #[inline]
+    fn component_id(world: &World) -> ComponentId {
+        world.get_component_id::<C>().expect("QueryItem component index not found")
+    }
+
+    fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
+        access.add_mutable(world.register_component::<C>().get())
+    }
+}
+
 pub struct Children<'a>(&'a [Entity]);
 
 impl<'a> Deref for Children<'a> {
@@ -311,17 +844,84 @@ impl<'a> Deref for Children<'a> {
 impl QueryItem for Children<'_> {
     type ItemRef<'a> = Children<'a>;
     type ItemMut<'a> = Children<'a>;
+    type Fetch<'a> = ();
 
     #[inline]
-    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, _: ComponentId) -> Self::ItemRef<'_> {
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, _: ComponentId, _: SystemTicks) -> Self::ItemRef<'_> {
         Children(unsafe { world_ptr.as_world() }.children(entity))
     }
 
     #[inline]
-    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, _: ComponentId) -> Self::ItemMut<'_> {
+    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, _: ComponentId, _: SystemTicks) -> Self::ItemMut<'_> {
         Children(unsafe { world_ptr.as_world() }.children(entity))
     }
 
+    #[inline]
+    unsafe fn init_fetch(_: WorldPtr<'_>, _: usize, _: ComponentId) -> Self::Fetch<'_> {}
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(_: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, _: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a> {
+        unsafe { <Self as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(_: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, _: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a> {
+        unsafe { <Self as QueryItem>::fetch_mut(world_ptr, entity, component_index, ticks) }
+    }
+
+    fn component_id_or_init(_: &mut World) -> ComponentId {
+        unsafe { std::mem::transmute(usize::MAX) }
+    }
+
+    fn component_id(_: &World) -> ComponentId {
+        unsafe { std::mem::transmute(usize::MAX) }
+    }
+}
+
+pub struct Parent(Option<Entity>);
+
+impl Parent {
+    #[inline]
+    pub const fn get(&self) -> Option<Entity> {
+        self.0
+    }
+}
+
+impl Deref for Parent {
+    type Target = Option<Entity>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl QueryItem for Parent {
+    type ItemRef<'a> = Parent;
+    type ItemMut<'a> = Parent;
+    type Fetch<'a> = ();
+
+    #[inline]
+    unsafe fn fetch_ref(world_ptr: WorldPtr<'_>, entity: Entity, _: ComponentId, _: SystemTicks) -> Self::ItemRef<'_> {
+        Parent(unsafe { world_ptr.as_world() }.parent(entity))
+    }
+
+    #[inline]
+    unsafe fn fetch_mut(world_ptr: WorldPtr<'_>, entity: Entity, _: ComponentId, _: SystemTicks) -> Self::ItemMut<'_> {
+        Parent(unsafe { world_ptr.as_world() }.parent(entity))
+    }
+
+    #[inline]
+    unsafe fn init_fetch(_: WorldPtr<'_>, _: usize, _: ComponentId) -> Self::Fetch<'_> {}
+
+    #[inline(always)]
+    unsafe fn fetch_row_ref<'a>(_: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, _: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemRef<'a> {
+        unsafe { <Self as QueryItem>::fetch_ref(world_ptr, entity, component_index, ticks) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch_row_mut<'a>(_: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, _: usize, component_index: ComponentId, ticks: SystemTicks) -> Self::ItemMut<'a> {
+        unsafe { <Self as QueryItem>::fetch_mut(world_ptr, entity, component_index, ticks) }
+    }
+
     fn component_id_or_init(_: &mut World) -> ComponentId {
         unsafe { std::mem::transmute(usize::MAX) }
     }
@@ -334,8 +934,18 @@ impl QueryItem for Children<'_> {
 pub trait QueryData: Sync + Send {
     type ItemRef<'a>;
     type ItemMut<'a>;
-    unsafe fn fetch_ref<'a>(world_ptr: WorldPtr<'a>, entity: Entity, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::ItemRef<'a>;
-    unsafe fn fetch_mut<'a>(world_ptr: WorldPtr<'a>, entity: Entity, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::ItemMut<'a>;
+    type Fetch<'a>;
+    unsafe fn fetch_ref<'a>(world_ptr: WorldPtr<'a>, entity: Entity, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], ticks: SystemTicks) -> Self::ItemRef<'a>;
+    unsafe fn fetch_mut<'a>(world_ptr: WorldPtr<'a>, entity: Entity, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], ticks: SystemTicks) -> Self::ItemMut<'a>;
+    /// # Safety
+    /// Table_id must belong to the world
+    unsafe fn init_fetch<'a>(world_ptr: WorldPtr<'a>, table_id: usize, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::Fetch<'a>;
+    /// # Safety
+    /// Entity must be at the row of the table the fetch was initialized with
+    unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], ticks: SystemTicks) -> Self::ItemRef<'a>;
+    /// # Safety
+    /// Entity must be at the row of the table the fetch was initialized with
+    unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], ticks: SystemTicks) -> Self::ItemMut<'a>;
     fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict>;
     fn cache_component_ids(world: &World) -> [ComponentId; QUERY_MAX_VARIADIC_COUNT];
 }
@@ -346,15 +956,31 @@ macro_rules! query_tuple_impl {
         impl<$($name: QueryItem),+> QueryData for ($($name),+) {
             type ItemRef<'a> = ($($name::ItemRef<'a>),+);
             type ItemMut<'a> = ($($name::ItemMut<'a>),+);
+            type Fetch<'a> = ($($name::Fetch<'a>,)+);
 
             #[inline(always)]
-            unsafe fn fetch_ref<'a>(world_ptr: WorldPtr<'a>, entity: Entity, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::ItemRef<'a> {
-                unsafe { ($($name::fetch_ref(world_ptr, entity, component_indices[$i])),+) }
+            unsafe fn fetch_ref<'a>(world_ptr: WorldPtr<'a>, entity: Entity, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], ticks: SystemTicks) -> Self::ItemRef<'a> {
+                unsafe { ($($name::fetch_ref(world_ptr, entity, component_indices[$i], ticks)),+) }
             }
 
             #[inline(always)]
-            unsafe fn fetch_mut<'a>(world_ptr: WorldPtr<'a>, entity: Entity, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::ItemMut<'a> {
-                unsafe { ($($name::fetch_mut(world_ptr, entity, component_indices[$i])),+) }
+            unsafe fn fetch_mut<'a>(world_ptr: WorldPtr<'a>, entity: Entity, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], ticks: SystemTicks) -> Self::ItemMut<'a> {
+                unsafe { ($($name::fetch_mut(world_ptr, entity, component_indices[$i], ticks)),+) }
+            }
+
+            #[inline]
+            unsafe fn init_fetch<'a>(world_ptr: WorldPtr<'a>, table_id: usize, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::Fetch<'a> {
+                unsafe { ($($name::init_fetch(world_ptr, table_id, component_indices[$i]),)+) }
+            }
+
+            #[inline(always)]
+            unsafe fn fetch_row_ref<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], ticks: SystemTicks) -> Self::ItemRef<'a> {
+                unsafe { ($($name::fetch_row_ref(&fetch.$i, world_ptr, entity, row, component_indices[$i], ticks)),+) }
+            }
+
+            #[inline(always)]
+            unsafe fn fetch_row_mut<'a>(fetch: &Self::Fetch<'a>, world_ptr: WorldPtr<'a>, entity: Entity, row: usize, component_indices: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], ticks: SystemTicks) -> Self::ItemMut<'a> {
+                unsafe { ($($name::fetch_row_mut(&fetch.$i, world_ptr, entity, row, component_indices[$i], ticks)),+) }
             }
 
             #[inline]
@@ -377,46 +1003,204 @@ variadics_please::all_tuples_enumerated!{query_tuple_impl, 1, 32, D}
 impl QueryData for () {
     type ItemRef<'a> = ();
     type ItemMut<'a> = ();
+    type Fetch<'a> = ();
     fn cache_component_ids(_: &World) -> [ComponentId; QUERY_MAX_VARIADIC_COUNT] {
         std::array::from_fn(|_| unsafe { std::mem::transmute(usize::MAX) })
     }
     fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> { Ok(()) }
-    unsafe fn fetch_ref<'a>(_: WorldPtr<'a>, _: Entity, _: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::ItemRef<'a> {}
-    unsafe fn fetch_mut<'a>(_: WorldPtr<'a>, _: Entity, _: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::ItemMut<'a> {}
+    unsafe fn fetch_ref<'a>(_: WorldPtr<'a>, _: Entity, _: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], _: SystemTicks) -> Self::ItemRef<'a> {}
+    unsafe fn fetch_mut<'a>(_: WorldPtr<'a>, _: Entity, _: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], _: SystemTicks) -> Self::ItemMut<'a> {}
+    unsafe fn init_fetch<'a>(_: WorldPtr<'a>, _: usize, _: &[ComponentId; QUERY_MAX_VARIADIC_COUNT]) -> Self::Fetch<'a> {}
+    unsafe fn fetch_row_ref<'a>(_: &Self::Fetch<'a>, _: WorldPtr<'a>, _: Entity, _: usize, _: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], _: SystemTicks) -> Self::ItemRef<'a> {}
+    unsafe fn fetch_row_mut<'a>(_: &Self::Fetch<'a>, _: WorldPtr<'a>, _: Entity, _: usize, _: &[ComponentId; QUERY_MAX_VARIADIC_COUNT], _: SystemTicks) -> Self::ItemMut<'a> {}
 }
 
 pub trait QueryFilter {
+    type State: Clone + Send + Sync;
+    /// What the filter reads from a table, resolved once before iterating its rows
+    type Fetch<'a>;
     fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict>;
+    fn init_state(world: &mut World) -> Self::State;
+    /// Per entity check, runs after the entity's signature already matched
+    ///
+    /// # Safety
+    /// `world_ptr` must be valid for reading the components in `state`
+    unsafe fn filter(world_ptr: WorldPtr<'_>, entity: Entity, state: &Self::State, ticks: SystemTicks) -> bool;
+    /// # Safety
+    /// Table_id must belong to the world
+    unsafe fn init_fetch<'a>(world_ptr: WorldPtr<'a>, table_id: usize, state: &Self::State) -> Self::Fetch<'a>;
+    /// Per row check while iterating a table, runs after the table and the entity's sparse set components already matched
+    ///
+    /// # Safety
+    /// Entity must be at the row of the table the fetch was initialized with
+    unsafe fn filter_row(fetch: &Self::Fetch<'_>, world_ptr: WorldPtr<'_>, entity: Entity, row: usize, state: &Self::State, ticks: SystemTicks) -> bool;
 }
 
 macro_rules! query_filter_impl {
-    ($($name:ident),+) => {
+    ($(($i:tt, $name:ident)),+) => {
         impl<$($name: QueryFilter),+> QueryFilter for ($($name),+) {
+            type State = ($($name::State),+);
+            type Fetch<'a> = ($($name::Fetch<'a>),+);
+
             fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
                 $($name::join_filtered_component_access(world, access)?;)+
                 Ok(())
             }
+
+            fn init_state(world: &mut World) -> Self::State {
+                ($($name::init_state(world)),+)
+            }
+
+            #[inline]
+            unsafe fn filter(world_ptr: WorldPtr<'_>, entity: Entity, state: &Self::State, ticks: SystemTicks) -> bool {
+                unsafe { $($name::filter(world_ptr, entity, &state.$i, ticks))&&+ }
+            }
+
+            #[inline]
+            unsafe fn init_fetch<'a>(world_ptr: WorldPtr<'a>, table_id: usize, state: &Self::State) -> Self::Fetch<'a> {
+                unsafe { ($($name::init_fetch(world_ptr, table_id, &state.$i)),+) }
+            }
+
+            #[inline(always)]
+            unsafe fn filter_row(fetch: &Self::Fetch<'_>, world_ptr: WorldPtr<'_>, entity: Entity, row: usize, state: &Self::State, ticks: SystemTicks) -> bool {
+                unsafe { $($name::filter_row(&fetch.$i, world_ptr, entity, row, &state.$i, ticks))&&+ }
+            }
         }
     }
 }
 
 impl QueryFilter for () {
+    type State = ();
+    type Fetch<'a> = ();
     fn join_filtered_component_access(_: &mut World, _: &mut FilteredComponentAccess) -> Result<(), Conflict> { Ok(()) }
+    fn init_state(_: &mut World) -> Self::State {}
+    #[inline]
+    unsafe fn filter(_: WorldPtr<'_>, _: Entity, _: &Self::State, _: SystemTicks) -> bool { true }
+    #[inline]
+    unsafe fn init_fetch<'a>(_: WorldPtr<'a>, _: usize, _: &Self::State) -> Self::Fetch<'a> {}
+    #[inline(always)]
+    unsafe fn filter_row(_: &Self::Fetch<'_>, _: WorldPtr<'_>, _: Entity, _: usize, _: &Self::State, _: SystemTicks) -> bool { true }
 }
 
-variadics_please::all_tuples!{query_filter_impl, 2, 32, C}
+variadics_please::all_tuples_enumerated!{query_filter_impl, 2, 32, C}
 
 pub struct With<B: ComponentBundle + 'static>(PhantomData<B>);
 pub struct Without<B: ComponentBundle + 'static>(PhantomData<B>);
 
 impl<B: ComponentBundle + 'static> QueryFilter for With<B> {
+    type State = ();
+    type Fetch<'a> = ();
     fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
         access.join_with(B::signature(world))
     }
+    fn init_state(_: &mut World) -> Self::State {}
+    #[inline]
+    unsafe fn filter(_: WorldPtr<'_>, _: Entity, _: &Self::State, _: SystemTicks) -> bool { true }
+    #[inline]
+    unsafe fn init_fetch<'a>(_: WorldPtr<'a>, _: usize, _: &Self::State) -> Self::Fetch<'a> {}
+    #[inline(always)]
+    unsafe fn filter_row(_: &Self::Fetch<'_>, _: WorldPtr<'_>, _: Entity, _: usize, _: &Self::State, _: SystemTicks) -> bool { true }
+}
+
+/// Makes the query match disabled entities too
+pub struct IncludeDisabled;
+
+impl QueryFilter for IncludeDisabled {
+    type State = ();
+    type Fetch<'a> = ();
+    fn join_filtered_component_access(_: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
+        access.set_include_disabled();
+        Ok(())
+    }
+    fn init_state(_: &mut World) -> Self::State {}
+    #[inline]
+    unsafe fn filter(_: WorldPtr<'_>, _: Entity, _: &Self::State, _: SystemTicks) -> bool { true }
+    #[inline]
+    unsafe fn init_fetch<'a>(_: WorldPtr<'a>, _: usize, _: &Self::State) -> Self::Fetch<'a> {}
+    #[inline(always)]
+    unsafe fn filter_row(_: &Self::Fetch<'_>, _: WorldPtr<'_>, _: Entity, _: usize, _: &Self::State, _: SystemTicks) -> bool { true }
 }
 
 impl<B: ComponentBundle + 'static> QueryFilter for Without<B> {
+    type State = ();
+    type Fetch<'a> = ();
     fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
         access.join_without(B::signature(world))
     }
+    fn init_state(_: &mut World) -> Self::State {}
+    #[inline]
+    unsafe fn filter(_: WorldPtr<'_>, _: Entity, _: &Self::State, _: SystemTicks) -> bool { true }
+    #[inline]
+    unsafe fn init_fetch<'a>(_: WorldPtr<'a>, _: usize, _: &Self::State) -> Self::Fetch<'a> {}
+    #[inline(always)]
+    unsafe fn filter_row(_: &Self::Fetch<'_>, _: WorldPtr<'_>, _: Entity, _: usize, _: &Self::State, _: SystemTicks) -> bool { true }
+}
+
+/// Matches entities whose `C` was added since the system last ran
+pub struct Added<C: Component>(PhantomData<C>);
+
+impl<C: Component> QueryFilter for Added<C> {
+    type State = ComponentId;
+    type Fetch<'a> = Option<&'a Column>;
+    fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
+        join_ticks_access::<C>(world, access)
+    }
+    fn init_state(world: &mut World) -> Self::State {
+        world.register_component::<C>()
+    }
+    #[inline]
+    unsafe fn filter(world_ptr: WorldPtr<'_>, entity: Entity, component_id: &Self::State, ticks: SystemTicks) -> bool {
+        unsafe { world_ptr.as_world() }
+            .get_component_ticks_by_id(entity, *component_id)
+            .is_some_and(|component_ticks| ticks.is_added(&component_ticks))
+    }
+    #[inline]
+    unsafe fn init_fetch<'a>(world_ptr: WorldPtr<'a>, table_id: usize, component_id: &Self::State) -> Self::Fetch<'a> {
+        unsafe { table_column(world_ptr, table_id, *component_id) }
+    }
+    #[inline(always)]
+    unsafe fn filter_row(fetch: &Self::Fetch<'_>, world_ptr: WorldPtr<'_>, entity: Entity, row: usize, component_id: &Self::State, ticks: SystemTicks) -> bool {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { Self::filter(world_ptr, entity, component_id, ticks) };
+        }
+        fetch.is_some_and(|column| ticks.is_added(column.get_ticks(row)))
+    }
+}
+
+/// Matches entities whose `C` was added or written to since the system last ran
+impl<C: Component> QueryFilter for Changed<C> {
+    type State = ComponentId;
+    type Fetch<'a> = Option<&'a Column>;
+    fn join_filtered_component_access(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
+        join_ticks_access::<C>(world, access)
+    }
+    fn init_state(world: &mut World) -> Self::State {
+        world.register_component::<C>()
+    }
+    #[inline]
+    unsafe fn filter(world_ptr: WorldPtr<'_>, entity: Entity, component_id: &Self::State, ticks: SystemTicks) -> bool {
+        unsafe { world_ptr.as_world() }
+            .get_component_ticks_by_id(entity, *component_id)
+            .is_some_and(|component_ticks| ticks.is_changed(&component_ticks))
+    }
+    #[inline]
+    unsafe fn init_fetch<'a>(world_ptr: WorldPtr<'a>, table_id: usize, component_id: &Self::State) -> Self::Fetch<'a> {
+        unsafe { table_column(world_ptr, table_id, *component_id) }
+    }
+    #[inline(always)]
+    unsafe fn filter_row(fetch: &Self::Fetch<'_>, world_ptr: WorldPtr<'_>, entity: Entity, row: usize, component_id: &Self::State, ticks: SystemTicks) -> bool {
+        if C::STORAGE_TYPE == StorageType::SparseSet {
+            return unsafe { Self::filter(world_ptr, entity, component_id, ticks) };
+        }
+        fetch.is_some_and(|column| ticks.is_changed(column.get_ticks(row)))
+    }
+}
+
+// reading the ticks is a read of the component, unless the query already writes it
+fn join_ticks_access<C: Component>(world: &mut World, access: &mut FilteredComponentAccess) -> Result<(), Conflict> {
+    let component_id = world.register_component::<C>().get();
+    if access.mutable().get(component_id) {
+        return Ok(());
+    }
+    access.add_immutable(component_id)
 }
@@ -0,0 +1,121 @@
+use std::{any::TypeId, collections::HashMap};
+
+use crate::{Commands, Entity, storage::sparse_set::SparseSet};
+
+/// What happens to the sources of a relation when its target is despawned
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RelationDespawnPolicy {
+    /// Sources lose the relation and stay alive
+    #[default]
+    Unrelate,
+    /// Sources are despawned together with the target
+    DespawnSources,
+}
+
+/// Typed relation between two entities, `world.relate::<R>(source, target)` reads as "source R target".
+pub trait Relationship: Send + Sync + 'static {
+    /// Source can only be related to a single target, relating it again replaces the previous target
+    const EXCLUSIVE: bool = false;
+    const DESPAWN_POLICY: RelationDespawnPolicy = RelationDespawnPolicy::Unrelate;
+}
+
+struct RelationStorage {
+    // source -> targets
+    targets: SparseSet<Vec<Entity>>,
+    // target -> sources
+    sources: SparseSet<Vec<Entity>>,
+    exclusive: bool,
+    despawn_policy: RelationDespawnPolicy,
+}
+
+impl RelationStorage {
+    fn unlink(&mut self, source: Entity, target: Entity) {
+        remove_from_list(&mut self.targets, source, target);
+        remove_from_list(&mut self.sources, target, source);
+    }
+}
+
+fn remove_from_list(lists: &mut SparseSet<Vec<Entity>>, key: Entity, value: Entity) {
+    let Some(list) = lists.get_mut(key.id() as usize) else { return; };
+    if let Some(index) = list.iter().position(|p| *p == value) {
+        list.remove(index);
+    }
+    if list.is_empty() {
+        lists.remove(key.id() as usize);
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Relations {
+    ids: HashMap<TypeId, usize>,
+    storages: Vec<RelationStorage>,
+}
+
+impl Relations {
+    fn storage<R: Relationship>(&self) -> Option<&RelationStorage> {
+        let id = *self.ids.get(&TypeId::of::<R>())?;
+        Some(&self.storages[id])
+    }
+
+    fn storage_mut<R: Relationship>(&mut self) -> &mut RelationStorage {
+        let id = *self.ids.entry(TypeId::of::<R>()).or_insert_with(|| {
+            self.storages.push(RelationStorage {
+                targets: SparseSet::default(),
+                sources: SparseSet::default(),
+                exclusive: R::EXCLUSIVE,
+                despawn_policy: R::DESPAWN_POLICY,
+            });
+            self.storages.len() - 1
+        });
+        &mut self.storages[id]
+    }
+
+    /// Both entities must be alive
+    pub(crate) fn relate<R: Relationship>(&mut self, source: Entity, target: Entity) {
+        let storage = self.storage_mut::<R>();
+        let targets = storage.targets.entry(source.id() as usize).or_default();
+        if targets.contains(&target) { return; }
+        if storage.exclusive {
+            for old_target in std::mem::take(targets) {
+                remove_from_list(&mut storage.sources, old_target, source);
+            }
+        }
+        storage.targets.entry(source.id() as usize).or_default().push(target);
+        storage.sources.entry(target.id() as usize).or_default().push(source);
+    }
+
+    pub(crate) fn unrelate<R: Relationship>(&mut self, source: Entity, target: Entity) {
+        let Some(id) = self.ids.get(&TypeId::of::<R>()) else { return; };
+        self.storages[*id].unlink(source, target);
+    }
+
+    pub(crate) fn targets<R: Relationship>(&self, source: Entity) -> &[Entity] {
+        self.storage::<R>()
+            .and_then(|storage| storage.targets.get(source.id() as usize))
+            .map_or(&[], |targets| targets.as_slice())
+    }
+
+    pub(crate) fn sources<R: Relationship>(&self, target: Entity) -> &[Entity] {
+        self.storage::<R>()
+            .and_then(|storage| storage.sources.get(target.id() as usize))
+            .map_or(&[], |sources| sources.as_slice())
+    }
+
+    pub(crate) fn despawn(&mut self, entity: Entity, commands: &mut Commands) {
+        for storage in self.storages.iter_mut() {
+            if let Some(targets) = storage.targets.remove(entity.id() as usize) {
+                for target in targets {
+                    remove_from_list(&mut storage.sources, target, entity);
+                }
+            }
+            if let Some(sources) = storage.sources.remove(entity.id() as usize) {
+                for source in sources {
+                    remove_from_list(&mut storage.targets, source, entity);
+                    if storage.despawn_policy == RelationDespawnPolicy::DespawnSources {
+                        commands.despawn(source);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 pub mod error;
 use std::{any::{Any, TypeId}, collections::HashMap, fmt::Debug, hash::Hash, ptr::NonNull, sync::Arc};
 
-use crate::{access::Access, schedule::error::ScheduleRunError, system::{IntoSystem, System, SystemId, SystemInput, SystemOutput, error::InternalSystemError}, world::{World, WorldId, WorldPtr}};
+use crate::{access::Access, change_detection::Tick, schedule::error::ScheduleRunError, system::{IntoSystem, System, SystemId, SystemInput, SystemOutput, error::InternalSystemError}, world::{World, WorldId, WorldPtr}};
 
 pub(crate) const PARALLEL_EXECUTION_THRESHOLD: usize = 4;
 
@@ -12,6 +12,8 @@ pub struct Schedule {
     parallel_execution_queue: Vec<ParallelBucket>,
     init_queue: Vec<Box<dyn System<Input = ()> + Send + Sync>>,
     label_debug: Option<Arc<str>>, // TODO temp, replace with label
+    // change tick the systems' last runs were last clamped at
+    last_check_tick: Tick,
 }
 
 struct SystemRecord {
@@ -72,6 +74,8 @@ impl Schedule {
 
     pub(crate) fn execute(&mut self, mut world_ptr: WorldPtr<'_>) {
         for bucket in &self.parallel_execution_queue {
+            // no system is running between the buckets, the ones reading an index see the values written before
+            unsafe { world_ptr.as_world_mut() }.refresh_indexes();
             if bucket.should_run_paralell {
                 unsafe { world_ptr.as_world() }.thread_pool.in_place_scope(|scope| {
                     for mut system_ptr in bucket.systems.iter().copied() {
@@ -120,8 +124,18 @@ impl Schedule {
             }
         }
 
+        world.check_change_ticks();
+        let change_tick = world.change_tick();
+        if change_tick.needs_check(self.last_check_tick) {
+            for record in self.system_records.iter_mut() {
+                record.system.check_change_tick(change_tick);
+            }
+            self.last_check_tick = change_tick;
+        }
+
         self.execute(world.world_ptr_mut());
         world.process_command_buffer();
+        world.entities.end_schedule_run();
     }
 
     pub fn remove_system_at(&mut self, index: usize) {
@@ -9,7 +9,7 @@ pub struct BlobVec<A: Allocator = Global> {
     raw: RawBlobVec<A>,
     len: usize,
     item_layout: Layout,
-    drop: unsafe fn(NonNull<u8>),
+    drop: unsafe fn(PtrMut<'_>),
 }
 
 impl BlobVec<Global> {
@@ -19,13 +19,25 @@ impl BlobVec<Global> {
             raw: RawBlobVec::new(Global),
             len: 0,
             item_layout: Layout::new::<T>(),
-            drop: |ptr| {
-                let ptr = ptr.cast::<T>();
-                unsafe { ptr.drop_in_place() };
+            drop: |mut ptr| {
+                unsafe { ptr.as_ptr().cast::<T>().drop_in_place() };
             },
         }
     }
 }
+impl BlobVec<Global> {
+    /// BlobVec of items only known by their layout, drop runs on every item that is popped or cleared
+    #[inline]
+    pub const fn from_layout(item_layout: Layout, drop: unsafe fn(PtrMut<'_>)) -> BlobVec<Global> {
+        BlobVec {
+            raw: RawBlobVec::new(Global),
+            len: 0,
+            item_layout,
+            drop,
+        }
+    }
+}
+
 impl<A: Allocator> BlobVec<A> {
     #[inline]
     pub const fn new_in<T>(alloc: A) -> Self {
@@ -33,9 +45,8 @@ impl<A: Allocator> BlobVec<A> {
             raw: RawBlobVec::new(alloc),
             len: 0,
             item_layout: Layout::new::<T>(),
-            drop: |ptr| {
-                let ptr = ptr.cast::<T>();
-                unsafe { ptr.drop_in_place() };
+            drop: |mut ptr| {
+                unsafe { ptr.as_ptr().cast::<T>().drop_in_place() };
             },
         }
     }
@@ -59,6 +70,15 @@ impl<A: Allocator> BlobVec<A> {
         }
     }
 
+    /// Reserves capacity for at least additional more elements
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        // zero sized items never allocate, so their len runs past the capacity
+        if additional > self.raw.capacity.saturating_sub(self.len) {
+            self.raw.grow_amortized(self.len, additional, self.item_layout);
+        }
+    }
+
     #[inline]
     pub fn get<'a>(&mut self, index: usize) -> Option<Ptr<'a>> {
         let len = self.len;
@@ -109,11 +129,24 @@ impl<A: Allocator> BlobVec<A> {
         PtrMut::new(ptr)
     }
 
+    /// Drops a value that isn't stored in the BlobVec
+    /// # Safety
+    /// ptr must point to a valid value of the type used to create the BlobVec, which must not be used afterwards
+    #[inline]
+    pub unsafe fn drop_value(&self, ptr: PtrMut<'_>) {
+        unsafe { (self.drop)(ptr) }
+    }
+
     #[inline]
     pub const fn item_layout(&self) -> Layout {
         self.item_layout
     }
 
+    #[inline]
+    pub const fn drop_fn(&self) -> unsafe fn(PtrMut<'_>) {
+        self.drop
+    }
+
     #[inline]
     pub const fn len(&self) -> usize {
         self.len
@@ -131,7 +164,7 @@ impl<A: Allocator> BlobVec<A> {
         let size = self.item_layout.size();
         unsafe {
             let ptr = self.raw.ptr.add(self.len * size);
-            (self.drop)(ptr)
+            (self.drop)(PtrMut::new(ptr))
         }
     }
 
@@ -149,6 +182,17 @@ impl<A: Allocator> BlobVec<A> {
         }
     }
 
+    /// Shortens the BlobVec by one without dropping the last item, which is left behind the returned pointer until the next push
+    /// # Safety
+    /// The BlobVec must not be empty, the item must be moved out or dropped by the caller
+    #[inline]
+    pub unsafe fn forget_last(&mut self) -> PtrMut<'_> {
+        debug_assert!(self.len > 0);
+        self.len -= 1;
+        let size = self.item_layout.size();
+        PtrMut::new(unsafe { self.raw.ptr.add(self.len * size) })
+    }
+
     #[inline]
     pub fn swap(&mut self, a: usize, b: usize) {
         assert!(a < self.len && b < self.len);
@@ -170,7 +214,7 @@ impl<A: Allocator> BlobVec<A> {
         let size = self.item_layout.size();
         for _ in 0..self.len {
             unsafe {
-                (self.drop)(ptr);
+                (self.drop)(PtrMut::new(ptr));
                 ptr = ptr.add(size);
             }
         }
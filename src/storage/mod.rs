@@ -1,3 +1,4 @@
 pub mod blob_vec;
 pub mod ptr;
 pub mod sparse_set;
+pub mod table;
@@ -1,10 +1,12 @@
 use std::{alloc::Layout, marker::PhantomData, ptr::NonNull};
-use crate::storage::{blob_vec::{BlobVec, Iter}, ptr::*, sparse_set::{SparseArray, SparseIndex}};
+use crate::{change_detection::{ComponentTicks, Tick}, storage::{blob_vec::{BlobVec, Iter}, ptr::*, sparse_set::{SparseArray, SparseIndex}}};
 
 pub struct BlobSparseSet {
     sparse_array: SparseArray,
     dense: BlobVec,
     mapping: Vec<usize>,
+    // ticks of the value at the same dense index
+    ticks: Vec<ComponentTicks>,
 }
 
 #[allow(unused)]
@@ -14,9 +16,27 @@ impl BlobSparseSet {
             sparse_array: SparseArray::new(),
             dense: BlobVec::new::<T>(),
             mapping: vec![],
+            ticks: vec![],
+        }
+    }
+
+    /// BlobSparseSet of values only known by their layout
+    pub const fn from_layout(item_layout: Layout, drop: unsafe fn(PtrMut<'_>)) -> Self {
+        Self {
+            sparse_array: SparseArray::new(),
+            dense: BlobVec::from_layout(item_layout, drop),
+            mapping: vec![],
+            ticks: vec![],
         }
     }
 
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.dense.reserve(additional);
+        self.mapping.reserve(additional);
+        self.ticks.reserve(additional);
+    }
+
     #[inline]
     pub fn iter<T>(&self) -> Iter<'_, T> {
         self.dense.iter()
@@ -48,17 +68,41 @@ impl BlobSparseSet {
         Some(unsafe { self.dense.index_mut(index).cast_mut::<T>() })
     }
 
+    #[inline]
+    pub fn get_ticks(&self, id: usize) -> Option<&ComponentTicks> {
+        let index = self.sparse_array.get(id).get()?;
+        Some(&self.ticks[index])
+    }
+
+    /// # Safety
+    /// Type T must be the same as the one used to create the BlobSparseSet
+    #[inline]
+    pub unsafe fn get_with_ticks<T>(&self, id: usize) -> Option<(&T, &ComponentTicks)> {
+        assert!(Layout::new::<T>() == self.dense.item_layout());
+        let index = self.sparse_array.get(id).get()?;
+        Some((unsafe { self.dense.index(index).cast_ref::<T>() }, &self.ticks[index]))
+    }
+
+    /// # Safety
+    /// Type T must be the same as the one used to create the BlobSparseSet
+    #[inline]
+    pub unsafe fn get_mut_with_ticks<T>(&mut self, id: usize) -> Option<(&mut T, &mut ComponentTicks)> {
+        assert!(Layout::new::<T>() == self.dense.item_layout());
+        let index = self.sparse_array.get(id).get()?;
+        Some((unsafe { self.dense.index_mut(index).cast_mut::<T>() }, &mut self.ticks[index]))
+    }
+
     #[inline]
     pub fn ptr(&self, id: usize) -> Ptr<'_> {
         let sparse_index = self.sparse_array.get(id);
-        let index = sparse_index.0;
+        let index = sparse_index.0 as usize;
         self.dense.index(index)
     }
 
     #[inline]
     pub fn ptr_mut(&mut self, id: usize) -> PtrMut<'_> {
         let sparse_index = self.sparse_array.get(id);
-        let index = sparse_index.0;
+        let index = sparse_index.0 as usize;
         self.dense.index_mut(index)
     }
 
@@ -76,13 +120,58 @@ impl BlobSparseSet {
         Some(self.dense.index_mut(index))
     }
 
+    #[inline]
+    pub fn get_ptr_with_ticks(&self, id: usize) -> Option<(Ptr<'_>, &ComponentTicks)> {
+        let index = self.sparse_array.get(id).get()?;
+        Some((self.dense.index(index), &self.ticks[index]))
+    }
+
+    #[inline]
+    pub fn get_mut_ptr_with_ticks<'a>(&'a mut self, id: usize) -> Option<(PtrMut<'a>, &'a mut ComponentTicks)> {
+        let index = self.sparse_array.get(id).get()?;
+        Some((self.dense.index_mut(index), &mut self.ticks[index]))
+    }
+
+    /// Moves the value behind ptr into the set, a replaced value is swapped back behind ptr and returns true
+    /// # Safety
+    /// ptr must point to a valid value of the type used to create the BlobSparseSet
+    #[inline]
+    pub unsafe fn insert_raw(&mut self, id: usize, mut value: PtrMut<'_>, tick: Tick) -> bool {
+        let ptr = unsafe { NonNull::new_unchecked(value.as_ptr()) };
+        unsafe { self.insert_ptr(id, ptr, tick) }.1
+    }
+
+    /// # Safety
+    /// ptr must point to a valid value of the type used to create the BlobSparseSet, which must not be used afterwards
+    #[inline]
+    pub unsafe fn drop_value(&self, value: PtrMut<'_>) {
+        unsafe { self.dense.drop_value(value) }
+    }
+
+    #[inline]
+    pub const fn item_layout(&self) -> Layout {
+        self.dense.item_layout()
+    }
+
+    #[inline]
+    pub const fn drop_fn(&self) -> unsafe fn(PtrMut<'_>) {
+        self.dense.drop_fn()
+    }
+
+    #[inline]
+    pub fn check_ticks(&mut self, change_tick: Tick) {
+        for ticks in self.ticks.iter_mut() {
+            ticks.check_ticks(change_tick);
+        }
+    }
+
     /// # Safety
     /// Type T must be the same as the one used to create the BlobSparseSet
     #[inline]
-    pub unsafe fn insert<T>(&mut self, id: usize, mut value: T) -> Option<T> {
+    pub unsafe fn insert<T>(&mut self, id: usize, mut value: T, tick: Tick) -> Option<T> {
         assert!(Layout::new::<T>() == self.dense.item_layout());
         let ptr = NonNull::from(&mut value).cast::<u8>();
-        let (_, was_present) = unsafe { self.insert_ptr(id, ptr) };
+        let (_, was_present) = unsafe { self.insert_ptr(id, ptr, tick) };
         match was_present {
             true => Some(value),
             false => {
@@ -96,27 +185,29 @@ impl BlobSparseSet {
     /// ptr must point to a value of type used to create the BlobSparseSet
     /// Value must be properly aligned and valid for reads
     #[inline]
-    unsafe fn insert_ptr(&mut self, id: usize, ptr: NonNull<u8>) -> (usize, bool) {
+    unsafe fn insert_ptr(&mut self, id: usize, ptr: NonNull<u8>, tick: Tick) -> (usize, bool) {
         let sparse_index = self.sparse_array.get(id); //        ^ was component present
         if let Some(index) = sparse_index.get() {
             let dst = self.dense.index_mut(index).as_ptr();
             let src = ptr.as_ptr();
             unsafe { std::ptr::swap_nonoverlapping(dst, src, self.dense.item_layout().size()) };
+            self.ticks[index].changed = tick;
             (index, true)
         } else {
             let index = self.dense.len();
             self.sparse_array.set(id, SparseIndex::new(index));
             unsafe { self.dense.push(ptr) };
             self.mapping.push(id);
+            self.ticks.push(ComponentTicks::new(tick));
             (index, false)
         }
     }
 
     #[inline]
-    fn insert_with_index<T>(&mut self, id: usize, mut value: T) -> usize {
+    fn insert_with_index<T>(&mut self, id: usize, mut value: T, tick: Tick) -> usize {
         assert!(Layout::new::<T>() == self.dense.item_layout());
         let ptr = NonNull::from(&mut value).cast::<u8>();
-        let index = unsafe { self.insert_ptr(id, ptr) }.0;
+        let index = unsafe { self.insert_ptr(id, ptr, tick) }.0;
         std::mem::forget(value);
         index
     }
@@ -131,6 +222,7 @@ impl BlobSparseSet {
 
         self.dense.swap(index, dense_len-1);
         self.mapping.swap(index, dense_len-1);
+        self.ticks.swap_remove(index);
 
         self.sparse_array.set(back, sparse_index);
         self.sparse_array.set(id, SparseIndex::NONE);
@@ -151,6 +243,7 @@ impl BlobSparseSet {
 
         self.dense.swap(index, dense_len-1);
         self.mapping.swap(index, dense_len-1);
+        self.ticks.swap_remove(index);
 
         self.sparse_array.set(back, sparse_index);
         self.sparse_array.set(id, SparseIndex::NONE);
@@ -165,8 +258,9 @@ impl BlobSparseSet {
 
         self.dense.swap(index, dense_len-1);
         self.mapping.swap(index, dense_len-1);
+        self.ticks.swap_remove(index);
 
-        self.sparse_array.set(back, SparseIndex(index));
+        self.sparse_array.set(back, SparseIndex::new(index));
         self.sparse_array.set(id, SparseIndex::NONE);
 
         self.mapping.pop();
@@ -197,6 +291,7 @@ impl BlobSparseSet {
     pub fn clear(&mut self) {
         self.sparse_array.clear();
         self.mapping.clear();
+        self.ticks.clear();
         self.dense.clear();
     }
 
@@ -320,12 +415,12 @@ pub struct VacantEntry<'a, V> {
 
 impl<'a, V> VacantEntry<'a, V> {
     pub fn insert(self, value: V) -> &'a mut V {
-        let index = self.sparse_set.insert_with_index(self.id, value);
+        let index = self.sparse_set.insert_with_index(self.id, value, Tick::default());
         unsafe { self.sparse_set.dense.index_mut(index).cast_mut::<V>() }
     }
 
     pub fn insert_entry(self, value: V) -> OccupiedEntry<'a, V> {
-        let index = self.sparse_set.insert_with_index(self.id, value);
+        let index = self.sparse_set.insert_with_index(self.id, value, Tick::default());
         OccupiedEntry {
             sparse_set: self.sparse_set,
             id: self.id,
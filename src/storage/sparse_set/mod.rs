@@ -29,17 +29,40 @@ impl<T> SparseSet<T> {
         }
     }
 
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.dense.reserve(additional);
+        self.mapping.reserve(additional);
+    }
+
     #[inline]
     pub fn iter(&self) -> Iter<T> {
         self.dense.iter()
     }
 
+    #[inline]
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.dense.iter_mut()
+    }
+
     #[inline]
     pub fn contains(&self, id: usize) -> bool {
         let sparse_index = self.sparse_array.get(id);
         sparse_index.is_some()
     }
 
+    /// Position of the id's value in the dense array
+    #[inline]
+    pub fn index_of(&self, id: usize) -> Option<usize> {
+        self.sparse_array.get(id).get()
+    }
+
+    /// Values with their ids
+    #[inline]
+    pub fn iter_with_ids_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+        self.mapping.iter().copied().zip(self.dense.iter_mut())
+    }
+
     #[inline]
     pub fn get(&self, id: usize) -> Option<&T> {
         let sparse_index = self.sparse_array.get(id);
@@ -57,14 +80,14 @@ impl<T> SparseSet<T> {
     #[inline]
     pub fn ptr(&self, id: usize) -> *const T {
         let sparse_index = self.sparse_array.get(id);
-        let index = sparse_index.0;
+        let index = sparse_index.0 as usize;
         &self.dense[index]
     }
 
     #[inline]
     pub fn ptr_mut(&mut self, id: usize) -> *mut T {
         let sparse_index = self.sparse_array.get(id);
-        let index = sparse_index.0;
+        let index = sparse_index.0 as usize;
         &mut self.dense[index]
     }
 
@@ -135,7 +158,7 @@ impl<T> SparseSet<T> {
         self.dense.swap(index, dense_len-1);
         self.mapping.swap(index, dense_len-1);
 
-        self.sparse_array.set(back, SparseIndex(index));
+        self.sparse_array.set(back, SparseIndex::new(index));
         self.sparse_array.set(id, SparseIndex::NONE);
 
         self.mapping.pop();
@@ -199,7 +222,7 @@ impl SparseArray {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
-pub struct SparseIndex(usize);
+pub struct SparseIndex(u32);
 
 impl std::fmt::Debug for SparseIndex {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -213,28 +236,29 @@ impl std::fmt::Debug for SparseIndex {
 
 #[allow(unused)]
 impl SparseIndex {
-    pub const NONE: Self = Self(usize::MAX);
-    pub const MAX: usize = usize::MAX - 1;
+    pub const NONE: Self = Self(u32::MAX);
+    pub const MAX: usize = u32::MAX as usize - 1;
 
     #[inline]
-    const fn new(index: usize) -> Self {
-        Self(index)
+    pub const fn new(index: usize) -> Self {
+        assert!(index <= Self::MAX, "sparse index overflow");
+        Self(index as u32)
     }
 
     #[inline]
     const fn is_some(&self) -> bool {
-        self.0 != usize::MAX
+        self.0 != u32::MAX
     }
     
     #[inline]
     const fn is_none(&self) -> bool {
-        self.0 == usize::MAX
+        self.0 == u32::MAX
     }
 
     #[inline]
     const fn get(&self) -> Option<usize> {
         if self.is_some() {
-            Some(self.0)
+            Some(self.0 as usize)
         } else {
             None
         }
@@ -242,12 +266,12 @@ impl SparseIndex {
 
     #[inline]
     const fn set(&mut self, index: usize) {
-        self.0 = index;
+        self.0 = index as u32;
     }
 
     #[inline]
     const unsafe fn get_unsafe(&self) -> usize {
-        self.0
+        self.0 as usize
     }
 }
 
@@ -0,0 +1,165 @@
+use std::{alloc::Layout, ptr::NonNull};
+use crate::{Entity, bitset::BitSet, group::Edges, change_detection::{ComponentTicks, Tick}, storage::{blob_vec::BlobVec, ptr::*, sparse_set::SparseSet}};
+
+/// Values of a single component, stored at the rows of their entities in the table
+pub struct Column {
+    data: BlobVec,
+    // ticks of the value at the same row
+    ticks: Vec<ComponentTicks>,
+}
+
+impl Column {
+    pub const fn new(item_layout: Layout, drop: unsafe fn(PtrMut<'_>)) -> Self {
+        Self {
+            data: BlobVec::from_layout(item_layout, drop),
+            ticks: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, row: usize) -> Ptr<'_> {
+        self.data.index(row)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, row: usize) -> PtrMut<'_> {
+        self.data.index_mut(row)
+    }
+
+    #[inline]
+    pub fn get_ticks(&self, row: usize) -> &ComponentTicks {
+        &self.ticks[row]
+    }
+
+    #[inline]
+    pub fn get_mut_with_ticks(&mut self, row: usize) -> (PtrMut<'_>, &mut ComponentTicks) {
+        (self.data.index_mut(row), &mut self.ticks[row])
+    }
+
+    /// Moves the value behind ptr to the end of the column
+    /// # Safety
+    /// ptr must point to a valid value of the column's type, which must not be used afterwards
+    #[inline]
+    pub unsafe fn push(&mut self, mut value: PtrMut<'_>, ticks: ComponentTicks) {
+        unsafe { self.data.push(NonNull::new_unchecked(value.as_ptr())) };
+        self.ticks.push(ticks);
+    }
+
+    /// Moves the last value into the row, the removed value is left behind the returned pointer until the next push
+    /// # Safety
+    /// Row must be in bounds, the value must be moved out or dropped by the caller
+    #[inline]
+    pub unsafe fn swap_remove_forget(&mut self, row: usize) -> (PtrMut<'_>, ComponentTicks) {
+        let last = self.data.len() - 1;
+        self.data.swap(row, last);
+        let ticks = self.ticks.swap_remove(row);
+        (unsafe { self.data.forget_last() }, ticks)
+    }
+
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+        self.ticks.reserve(additional);
+    }
+
+    #[inline]
+    pub fn check_ticks(&mut self, change_tick: Tick) {
+        for ticks in self.ticks.iter_mut() {
+            ticks.check_ticks(change_tick);
+        }
+    }
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// Entities sharing the same table components, every column holds one value per entity at the entity's row
+pub struct Table {
+    signature: BitSet,
+    entities: SparseSet<Entity>,
+    // keyed by component id
+    columns: SparseSet<Column>,
+    // tables reached by adding or removing a table component
+    edges: Edges,
+}
+
+impl Table {
+    pub fn new(signature: BitSet, columns: impl IntoIterator<Item = (usize, Column)>) -> Self {
+        let mut table = Self {
+            signature,
+            entities: SparseSet::new(),
+            columns: SparseSet::new(),
+            edges: Edges::default(),
+        };
+        for (component_id, column) in columns {
+            table.columns.insert(component_id, column);
+        }
+        table
+    }
+
+    #[inline]
+    pub const fn signature(&self) -> &BitSet {
+        &self.signature
+    }
+
+    #[inline]
+    pub fn row(&self, entity_id: usize) -> Option<usize> {
+        self.entities.index_of(entity_id)
+    }
+
+    #[inline]
+    pub fn entities(&self) -> &SparseSet<Entity> {
+        &self.entities
+    }
+
+    #[inline]
+    pub fn column(&self, component_id: usize) -> Option<&Column> {
+        self.columns.get(component_id)
+    }
+
+    #[inline]
+    pub fn column_mut(&mut self, component_id: usize) -> Option<&mut Column> {
+        self.columns.get_mut(component_id)
+    }
+
+    #[inline]
+    pub fn edges(&self) -> &Edges {
+        &self.edges
+    }
+
+    #[inline]
+    pub fn edges_mut(&mut self) -> &mut Edges {
+        &mut self.edges
+    }
+
+    /// Adds the entity at the last row, its values must be pushed to every column before the table is used again
+    #[inline]
+    pub fn push_entity(&mut self, entity: Entity) {
+        self.entities.insert(entity.id() as usize, entity);
+    }
+
+    /// Moves the entity's values out of the table, handing each one to f which must move it out or drop it
+    pub fn swap_remove(&mut self, entity_id: usize, mut f: impl FnMut(usize, PtrMut<'_>, ComponentTicks)) {
+        let Some(row) = self.row(entity_id) else { return; };
+        for (component_id, column) in self.columns.iter_with_ids_mut() {
+            let (value, ticks) = unsafe { column.swap_remove_forget(row) };
+            f(component_id, value, ticks);
+        }
+        self.entities.remove(entity_id);
+    }
+
+    pub fn check_ticks(&mut self, change_tick: Tick) {
+        for column in self.columns.iter_mut() {
+            column.check_ticks(change_tick);
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.entities.reserve(additional);
+        for column in self.columns.iter_mut() {
+            column.reserve(additional);
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use crate::{Component, ComponentBundle, Entity, IntoSystem, ObserverInput, Resource, ResourceId, ScheduleLabel, SystemInput, TriggerInput, World, entity::Entities, error::ECSError, param::{SystemParam, SystemParamError}, system::SystemOutput, world::WorldPtr};
+use crate::{Component, ComponentBundle, Entity, HierarchyDespawnPolicy, IntoSystem, ObserverInput, Relationship, Resource, ResourceId, ScheduleLabel, SystemInput, TriggerInput, World, entity::Entities, error::ECSError, param::{SystemParam, SystemParamError}, system::SystemOutput, world::WorldPtr};
 
-use super::{SystemHandle, SystemId};
+use super::{EntityCommands, SystemHandle, SystemId};
 
 pub struct Commands<'a> {
     queue: &'a mut Vec<u8>,
@@ -13,7 +13,19 @@ enum CommandMeta {
         data_size: usize,
         entity: Entity,
     },
-    Despawn(Entity),
+    SpawnBatch {
+        f: fn(&mut World, *mut u8),
+        data_size: usize,
+    },
+    InsertBundle {
+        f: fn(&mut World, *mut u8, Entity),
+        data_size: usize,
+        entity: Entity,
+    },
+    Despawn {
+        entity: Entity,
+        policy: Option<HierarchyDespawnPolicy>,
+    },
     SetComponent {
         f: fn(&mut World, *mut u8, Entity),
         entity: Entity,
@@ -23,6 +35,11 @@ enum CommandMeta {
         f: fn(&mut World, Entity),
         entity: Entity,
     },
+    TakeBundle {
+        f: fn(&mut World, *mut u8, Entity),
+        data_size: usize,
+        entity: Entity,
+    },
     SendTrigger {
         f: fn(&mut World, *mut u8, Option<Entity>),
         target: Option<Entity>,
@@ -68,12 +85,39 @@ enum CommandMeta {
     RemoveChildren {
         entity: Entity
     },
+    RemoveParent {
+        child: Entity,
+    },
+    SetDisabled {
+        entity: Entity,
+        disabled: bool,
+    },
+    InsertChildAt {
+        parent: Entity,
+        index: usize,
+        child: Entity,
+    },
+    MoveChild {
+        parent: Entity,
+        child: Entity,
+        index: usize,
+    },
+    SortChildren {
+        f: fn(&mut World, *mut u8, Entity),
+        data_size: usize,
+        parent: Entity,
+    },
+    Relation {
+        f: fn(&mut World, Entity, Entity),
+        source: Entity,
+        target: Entity,
+    },
     HandleError {
         error: ECSError,
     }
 }
 
-impl Commands<'_> {
+impl<'a> Commands<'a> {
     #[inline]
     fn copy_data<T>(&mut self, value: T, index: usize) {
         use std::ptr::NonNull;
@@ -83,7 +127,19 @@ impl Commands<'_> {
         std::mem::forget(value);
     }
 
-    pub fn spawn<B: ComponentBundle>(&mut self, bundle: B) -> Entity {
+    /// Builder queueing commands for an already existing entity
+    #[inline]
+    pub fn entity(&mut self, entity: Entity) -> EntityCommands<'_, 'a> {
+        EntityCommands::new(self, entity)
+    }
+
+    /// Reserves the entity immediately, the bundle is spawned when the commands are applied
+    pub fn spawn<B: ComponentBundle>(&mut self, bundle: B) -> EntityCommands<'_, 'a> {
+        let entity = self.spawn_entity(bundle);
+        EntityCommands::new(self, entity)
+    }
+
+    fn spawn_entity<B: ComponentBundle>(&mut self, bundle: B) -> Entity {
         let additional = size_of::<CommandMeta>() + size_of::<B>();
         let index = self.queue.len();
         self.queue.resize(self.queue.len() + additional, 0);
@@ -104,6 +160,57 @@ impl Commands<'_> {
         entity
     }
 
+    /// Reserves the entities immediately, the bundles are spawned when the commands are applied
+    pub fn spawn_batch<B: ComponentBundle, I: IntoIterator<Item = B>>(&mut self, bundles: I) -> Vec<Entity> {
+        let bundles: Vec<B> = bundles.into_iter().collect();
+        let entities = self.entities.spawn_batch(bundles.len());
+        let data = (entities.clone(), bundles);
+
+        let additional = size_of::<CommandMeta>() + size_of::<(Vec<Entity>, Vec<B>)>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::SpawnBatch {
+            f: |world, data| {
+                let data = data as *mut (Vec<Entity>, Vec<B>);
+                let (entities, bundles) = unsafe { data.read_unaligned() };
+                world.spawn_batch_reserved(&entities, bundles);
+            },
+            data_size: size_of::<(Vec<Entity>, Vec<B>)>(),
+        };
+
+        self.copy_data(command_meta, index);
+        self.copy_data(data, index + size_of::<CommandMeta>());
+        entities
+    }
+
+    pub(crate) fn insert_bundle<B: ComponentBundle>(&mut self, entity: Entity, bundle: B, if_new: bool) {
+        let additional = size_of::<CommandMeta>() + size_of::<B>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let f: fn(&mut World, *mut u8, Entity) = match if_new {
+            true => |world, data, entity| {
+                let data = data as *mut B;
+                let bundle = unsafe { data.read_unaligned() };
+                bundle.insert_if_new(entity, world);
+            },
+            false => |world, data, entity| {
+                let data = data as *mut B;
+                let bundle = unsafe { data.read_unaligned() };
+                bundle.insert(entity, world);
+            },
+        };
+        let command_meta = CommandMeta::InsertBundle {
+            f,
+            data_size: size_of::<B>(),
+            entity,
+        };
+
+        self.copy_data(command_meta, index);
+        self.copy_data(bundle, index + size_of::<CommandMeta>());
+    }
+
     pub fn set_component<C: Component>(&mut self, entity: Entity, component: C) {
         let additional = size_of::<CommandMeta>() + size_of::<C>();
         let index = self.queue.len();
@@ -123,12 +230,53 @@ impl Commands<'_> {
         self.copy_data(component, index + size_of::<CommandMeta>());
     }
 
+    /// Despawns the entity, its children are handled according to the world's [`HierarchyDespawnPolicy`]
+    #[inline]
     pub fn despawn(&mut self, entity: Entity) {
+        self.despawn_inner(entity, None);
+    }
+
+    #[inline]
+    pub fn despawn_recursive(&mut self, entity: Entity) {
+        self.despawn_inner(entity, Some(HierarchyDespawnPolicy::Recursive));
+    }
+
+    #[inline]
+    pub fn despawn_detach_children(&mut self, entity: Entity) {
+        self.despawn_inner(entity, Some(HierarchyDespawnPolicy::DetachChildren));
+    }
+
+    #[inline]
+    pub fn despawn_reparent_children(&mut self, entity: Entity) {
+        self.despawn_inner(entity, Some(HierarchyDespawnPolicy::ReparentChildren));
+    }
+
+    fn despawn_inner(&mut self, entity: Entity, policy: Option<HierarchyDespawnPolicy>) {
+        let additional = size_of::<CommandMeta>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::Despawn { entity, policy };
+
+        self.copy_data(command_meta, index);
+    }
+
+    pub fn disable(&mut self, entity: Entity) {
         let additional = size_of::<CommandMeta>();
         let index = self.queue.len();
         self.queue.resize(self.queue.len() + additional, 0);
 
-        let command_meta = CommandMeta::Despawn(entity);
+        let command_meta = CommandMeta::SetDisabled { entity, disabled: true };
+
+        self.copy_data(command_meta, index);
+    }
+
+    pub fn enable(&mut self, entity: Entity) {
+        let additional = size_of::<CommandMeta>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::SetDisabled { entity, disabled: false };
 
         self.copy_data(command_meta, index);
     }
@@ -148,6 +296,44 @@ impl Commands<'_> {
         self.copy_data(command_meta, index);
     }
 
+    /// Removes the bundle's components the entity has
+    pub fn remove_bundle<B: ComponentBundle>(&mut self, entity: Entity) {
+        let additional = size_of::<CommandMeta>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::RemoveComponent {
+            f: |world, entity| {
+                world.remove_bundle::<B>(entity);
+            },
+            entity,
+        };
+
+        self.copy_data(command_meta, index);
+    }
+
+    /// Takes the bundle out of the entity and passes it to f, f doesn't run if the entity lacks any of the bundle's components
+    pub fn take<B: ComponentBundle, F: FnOnce(&mut World, B) + Send + 'static>(&mut self, entity: Entity, f: F) {
+        let additional = size_of::<CommandMeta>() + size_of::<F>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::TakeBundle {
+            f: |world, data, entity| {
+                let data = data as *mut F;
+                let f = unsafe { data.read_unaligned() };
+                if let Some(bundle) = world.take::<B>(entity) {
+                    f(world, bundle);
+                }
+            },
+            data_size: size_of::<F>(),
+            entity,
+        };
+
+        self.copy_data(command_meta, index);
+        self.copy_data(f, index + size_of::<CommandMeta>());
+    }
+
     pub fn trigger<E: Send + Sync + 'static>(&mut self, event: E, target: Option<Entity>) {
         let additional = size_of::<CommandMeta>() + size_of::<E>();
         let index = self.queue.len();
@@ -315,6 +501,60 @@ impl Commands<'_> {
         self.copy_data(command_meta, index);
     }
 
+    #[inline]
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) {
+        self.add_child(parent, child);
+    }
+
+    pub fn remove_parent(&mut self, child: Entity) {
+        let additional = size_of::<CommandMeta>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+        
+        let command_meta = CommandMeta::RemoveParent { child };
+
+        self.copy_data(command_meta, index);
+    }
+
+    pub fn insert_child_at(&mut self, parent: Entity, index: usize, child: Entity) {
+        let additional = size_of::<CommandMeta>();
+        let queue_index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::InsertChildAt { parent, index, child };
+
+        self.copy_data(command_meta, queue_index);
+    }
+
+    pub fn move_child(&mut self, parent: Entity, child: Entity, index: usize) {
+        let additional = size_of::<CommandMeta>();
+        let queue_index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::MoveChild { parent, child, index };
+
+        self.copy_data(command_meta, queue_index);
+    }
+
+    pub fn sort_children_by_key<K: Ord, F: FnMut(&World, Entity) -> K + Send + 'static>(&mut self, parent: Entity, f: F) {
+        let additional = size_of::<CommandMeta>() + size_of::<F>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::SortChildren {
+            f: |world, data, parent| {
+                let data = data as *mut F;
+                let f = unsafe { data.read_unaligned() };
+                world.sort_children_by_key(parent, f);
+            },
+            data_size: size_of::<F>(),
+            parent,
+        };
+
+        self.copy_data(command_meta, index);
+        self.copy_data(f, index + size_of::<CommandMeta>());
+    }
+
     pub fn remove_children(&mut self, entity: Entity) {
         let additional = size_of::<CommandMeta>();
         let index = self.queue.len();
@@ -325,6 +565,38 @@ impl Commands<'_> {
         self.copy_data(command_meta, index);
     }
 
+    pub fn relate<R: Relationship>(&mut self, source: Entity, target: Entity) {
+        let additional = size_of::<CommandMeta>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::Relation {
+            f: |world, source, target| {
+                world.relate::<R>(source, target);
+            },
+            source,
+            target,
+        };
+
+        self.copy_data(command_meta, index);
+    }
+
+    pub fn unrelate<R: Relationship>(&mut self, source: Entity, target: Entity) {
+        let additional = size_of::<CommandMeta>();
+        let index = self.queue.len();
+        self.queue.resize(self.queue.len() + additional, 0);
+
+        let command_meta = CommandMeta::Relation {
+            f: |world, source, target| {
+                world.unrelate::<R>(source, target);
+            },
+            source,
+            target,
+        };
+
+        self.copy_data(command_meta, index);
+    }
+
     pub(crate) fn handle_error(&mut self, error: ECSError) {
         let additional = size_of::<CommandMeta>();
         let index = self.queue.len();
@@ -355,8 +627,21 @@ impl Commands<'_> {
                     (f)(world, ptr, entity);
                     cursor += data_size;
                 },
-                CommandMeta::Despawn(entity) => {
-                    world.despawn(entity);
+                CommandMeta::SpawnBatch { f, data_size } => {
+                    let ptr = unsafe { (&mut queue[0] as *mut u8).add(cursor) };
+                    (f)(world, ptr);
+                    cursor += data_size;
+                },
+                CommandMeta::InsertBundle { f, data_size, entity } => {
+                    let ptr = unsafe { (&mut queue[0] as *mut u8).add(cursor) };
+                    (f)(world, ptr, entity);
+                    cursor += data_size;
+                },
+                CommandMeta::Despawn { entity, policy } => {
+                    match policy {
+                        Some(policy) => world.despawn_with_policy(entity, policy),
+                        None => world.despawn(entity),
+                    }
                 },
                 CommandMeta::SetComponent { f, entity, data_size } => {
                     let ptr = unsafe { (&mut queue[0] as *mut u8).add(cursor) };
@@ -366,6 +651,11 @@ impl Commands<'_> {
                 CommandMeta::RemoveComponent { f, entity } => {
                     (f)(world, entity);
                 },
+                CommandMeta::TakeBundle { f, data_size, entity } => {
+                    let ptr = unsafe { (&mut queue[0] as *mut u8).add(cursor) };
+                    (f)(world, ptr, entity);
+                    cursor += data_size;
+                },
                 CommandMeta::SendTrigger { f, target, data_size } => {
                     let ptr = unsafe { (&mut queue[0] as *mut u8).add(cursor) };
                     (f)(world, ptr, target);
@@ -412,6 +702,29 @@ impl Commands<'_> {
                 CommandMeta::RemoveChildren { entity } => {
                     world.remove_children(entity);
                 },
+                CommandMeta::InsertChildAt { parent, index, child } => {
+                    world.insert_child_at(parent, index, child);
+                },
+                CommandMeta::MoveChild { parent, child, index } => {
+                    world.move_child(parent, child, index);
+                },
+                CommandMeta::SortChildren { f, data_size, parent } => {
+                    let ptr = unsafe { (&mut queue[0] as *mut u8).add(cursor) };
+                    (f)(world, ptr, parent);
+                    cursor += data_size;
+                },
+                CommandMeta::SetDisabled { entity, disabled } => {
+                    match disabled {
+                        true => world.disable(entity),
+                        false => world.enable(entity),
+                    }
+                },
+                CommandMeta::RemoveParent { child } => {
+                    world.remove_parent(child);
+                },
+                CommandMeta::Relation { f, source, target } => {
+                    (f)(world, source, target);
+                },
                 CommandMeta::HandleError { error } => {
                     world.handle_error(error);
                 }
@@ -425,7 +738,7 @@ impl Commands<'_> {
     }
 
     #[inline]
-    pub(crate) const fn new<'a>(buffer: &'a mut Vec<u8>, entities: &'a Entities) -> Commands<'a> {
+    pub(crate) const fn new<'b>(buffer: &'b mut Vec<u8>, entities: &'b Entities) -> Commands<'b> {
         Commands { queue: buffer, entities }
     }
 }
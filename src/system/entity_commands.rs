@@ -0,0 +1,94 @@
+use crate::{Commands, Component, ComponentBundle, Entity, World};
+
+/// Queues commands for a single entity, returned by [`Commands::spawn`] and [`Commands::entity`]
+pub struct EntityCommands<'c, 'a> {
+    commands: &'c mut Commands<'a>,
+    entity: Entity,
+}
+
+impl<'c, 'a> EntityCommands<'c, 'a> {
+    #[inline]
+    pub(crate) const fn new(commands: &'c mut Commands<'a>, entity: Entity) -> Self {
+        Self { commands, entity }
+    }
+
+    #[inline]
+    pub const fn id(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    pub fn commands(&mut self) -> &mut Commands<'a> {
+        self.commands
+    }
+
+    /// Adds the bundle's components, replacing the ones the entity already has
+    pub fn insert<B: ComponentBundle>(&mut self, bundle: B) -> &mut Self {
+        self.commands.insert_bundle(self.entity, bundle, false);
+        self
+    }
+
+    /// Adds the bundle's components the entity doesn't have yet
+    pub fn insert_if_new<B: ComponentBundle>(&mut self, bundle: B) -> &mut Self {
+        self.commands.insert_bundle(self.entity, bundle, true);
+        self
+    }
+
+    pub fn remove<C: Component>(&mut self) -> &mut Self {
+        self.commands.remove_component::<C>(self.entity);
+        self
+    }
+
+    /// Removes the bundle's components the entity has
+    pub fn remove_bundle<B: ComponentBundle>(&mut self) -> &mut Self {
+        self.commands.remove_bundle::<B>(self.entity);
+        self
+    }
+
+    /// Takes the bundle out of the entity and passes it to f, see [`Commands::take`]
+    pub fn take<B: ComponentBundle, F: FnOnce(&mut World, B) + Send + 'static>(&mut self, f: F) -> &mut Self {
+        self.commands.take::<B, F>(self.entity, f);
+        self
+    }
+
+    pub fn add_child(&mut self, child: Entity) -> &mut Self {
+        self.commands.add_child(self.entity, child);
+        self
+    }
+
+    /// Spawns children of the entity inside f
+    pub fn with_children<F: FnOnce(&mut ChildSpawner<'_, 'a>)>(&mut self, f: F) -> &mut Self {
+        f(&mut ChildSpawner { commands: self.commands, parent: self.entity });
+        self
+    }
+
+    /// Triggers the event with the entity as target
+    pub fn trigger<E: Send + Sync + 'static>(&mut self, event: E) -> &mut Self {
+        self.commands.trigger(event, Some(self.entity));
+        self
+    }
+
+    pub fn despawn(&mut self) {
+        self.commands.despawn(self.entity);
+    }
+}
+
+/// Spawns entities as children of a parent, see [`EntityCommands::with_children`]
+pub struct ChildSpawner<'c, 'a> {
+    commands: &'c mut Commands<'a>,
+    parent: Entity,
+}
+
+impl<'a> ChildSpawner<'_, 'a> {
+    #[inline]
+    pub const fn parent(&self) -> Entity {
+        self.parent
+    }
+
+    pub fn spawn<B: ComponentBundle>(&mut self, bundle: B) -> EntityCommands<'_, 'a> {
+        let parent = self.parent;
+        let child = self.commands.spawn(bundle);
+        child.commands.add_child(parent, child.entity);
+        child
+    }
+}
@@ -1,8 +1,10 @@
 mod commands;
+mod entity_commands;
 pub mod error;
 pub use commands::Commands;
+pub use entity_commands::{EntityCommands, ChildSpawner};
 use std::{any::TypeId, error::Error, marker::PhantomData, ops::{Deref, DerefMut}, sync::{Arc, atomic::{AtomicBool, Ordering}}};
-use crate::{access::AccessBuilder, error::ECSError, param::{SystemParam, SystemParamError}, system::error::InternalSystemError, world::WorldPtr};
+use crate::{access::AccessBuilder, change_detection::{SystemTicks, Tick}, error::ECSError, param::{SystemParam, SystemParamError}, system::error::InternalSystemError, world::WorldPtr};
 
 use super::{access::Access, World};
 
@@ -44,6 +46,8 @@ pub trait System {
     fn init(&mut self, world: &mut World) -> Result<(), InternalSystemError>;
     fn is_init(&self) -> bool;
     fn after<'a>(&'a mut self, commands: Commands<'a>);
+    /// Clamps the tick of the last run, see [`Tick::check_tick`]
+    fn check_change_tick(&mut self, change_tick: Tick);
 }
 
 pub struct FunctionSystem<ParamIn, Input, Output, F: SystemFunc<ParamIn, Input, Output>> {
@@ -55,6 +59,7 @@ pub struct FunctionSystem<ParamIn, Input, Output, F: SystemFunc<ParamIn, Input,
     is_init: bool,
     func: F,
     last_error: Option<ECSError>,
+    last_run: Tick,
     _a: std::marker::PhantomData<ParamIn>,
 }
 
@@ -64,12 +69,15 @@ impl<Input, ParamIn, F: SystemFunc<ParamIn, Input, ()>> System for FunctionSyste
     fn execute(&mut self, world_ptr: WorldPtr<'_>, input: Self::Input) {
         let name = self.name;
         let state = self.state.as_mut().unwrap_or_else(|| panic!("system '{}' has been executed without initialization", name));
+        let this_run = unsafe { world_ptr.as_world() }.increment_change_tick();
         let system_meta = SystemHandle {
             id: &self.id,
             name,
+            ticks: SystemTicks::new(self.last_run, this_run),
             _m: PhantomData,
         };
         self.func.run(world_ptr, state, input, system_meta);
+        self.last_run = this_run;
     }
 
     #[inline]
@@ -93,6 +101,7 @@ impl<Input, ParamIn, F: SystemFunc<ParamIn, Input, ()>> System for FunctionSyste
         let system_handle = SystemHandle {
             name: self.name,
             id: &self.id,
+            ticks: SystemTicks::default(),
             _m: PhantomData,
         };
         let mut access_builder = AccessBuilder::default();
@@ -118,6 +127,11 @@ impl<Input, ParamIn, F: SystemFunc<ParamIn, Input, ()>> System for FunctionSyste
     fn is_init(&self) -> bool {
         self.is_init
     }
+
+    #[inline]
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.last_run.check_tick(change_tick);
+    }
 }
 
 impl<Input, ParamIn, E: Error + Send + Sync + 'static, F: SystemFunc<ParamIn, Input, Result<(), E>>> System for FunctionSystem<ParamIn, Input, Result<(), E>, F> {
@@ -126,12 +140,15 @@ impl<Input, ParamIn, E: Error + Send + Sync + 'static, F: SystemFunc<ParamIn, In
     fn execute(&mut self, world_ptr: WorldPtr<'_>, input: Self::Input) {
         let name = self.name;
         let state = self.state.as_mut().unwrap_or_else(|| panic!("system '{}' has been executed without initialization", name));
+        let this_run = unsafe { world_ptr.as_world() }.increment_change_tick();
         let system_meta = SystemHandle {
             id: &self.id,
             name,
+            ticks: SystemTicks::new(self.last_run, this_run),
             _m: PhantomData,
         };
         self.last_error = self.func.run(world_ptr, state, input, system_meta).err().map(|err| ECSError::from(err));
+        self.last_run = this_run;
     }
 
     #[inline]
@@ -155,6 +172,7 @@ impl<Input, ParamIn, E: Error + Send + Sync + 'static, F: SystemFunc<ParamIn, In
         let system_handle = SystemHandle {
             name: self.name,
             id: &self.id,
+            ticks: SystemTicks::default(),
             _m: PhantomData,
         };
         let mut access_builder = AccessBuilder::default();
@@ -183,6 +201,11 @@ impl<Input, ParamIn, E: Error + Send + Sync + 'static, F: SystemFunc<ParamIn, In
     fn is_init(&self) -> bool {
         self.is_init
     }
+
+    #[inline]
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.last_run.check_tick(change_tick);
+    }
 }
 
 pub trait SystemFunc<ParamIn, Input, Output> {
@@ -340,6 +363,7 @@ where
             is_init: false,
             func: self,
             last_error: None,
+            last_run: Tick::default(),
             _a: Default::default(),
         }
     }
@@ -354,6 +378,7 @@ where
             is_init: false,
             func: self,
             last_error: None,
+            last_run: Tick::default(),
             _a: Default::default(),
         }
     }
@@ -376,6 +401,7 @@ where
             is_init: false,
             func: self,
             last_error: None,
+            last_run: Tick::default(),
             _a: Default::default(),
         }
     }
@@ -390,6 +416,7 @@ where
             is_init: false,
             func: self,
             last_error: None,
+            last_run: Tick::default(),
             _a: Default::default(),
         }
     }
@@ -417,6 +444,7 @@ impl<E: Error> SystemOutput for Result<(), E> {}
 pub struct SystemHandle<'a> {
     id: &'a SystemId,
     name: &'static str,
+    ticks: SystemTicks,
     _m: PhantomData<&'a u8>
 }
 
@@ -430,6 +458,12 @@ impl SystemHandle<'_> {
     pub const fn name(&self) -> &'static str {
         self.name
     }
+
+    /// Tick of the system's previous run and of the current one
+    #[inline]
+    pub const fn ticks(&self) -> SystemTicks {
+        self.ticks
+    }
 }
 
 unsafe impl SystemParam for &SystemHandle<'_> {
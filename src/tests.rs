@@ -1,10 +1,10 @@
-use crate::{bitmap::Bitmap, *};
+use crate::{*, group::Transitions, storage::sparse_set::SparseIndex};
 
 #[test]
 fn entities_despawn() {
     let mut entities = crate::entity::Entities::default();
     let a = entities.spawn();
-    entities.despawn(a, &mut vec![]);
+    entities.despawn(a, HierarchyDespawnPolicy::Recursive, &mut vec![]);
     assert!(!entities.is_alive(a));
 
     let mut alive_entities = Vec::new();
@@ -18,7 +18,7 @@ fn entities_despawn() {
         for j in 0..100 {
             let index = i * 1000 + j;
             let entity = alive_entities.remove(index);
-            entities.despawn(entity, &mut Vec::new());
+            entities.despawn(entity, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
             dead_entities.push(entity);
         }
     }
@@ -38,7 +38,7 @@ fn entities_despawn() {
     }
     while let Some(entity) = alive_entities.pop() {
         dead_entities.push(entity);
-        entities.despawn(entity, &mut Vec::new());
+        entities.despawn(entity, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
     }
     for entity in dead_entities.iter().copied() {
         assert!(!entities.is_alive(entity));
@@ -117,21 +117,22 @@ fn entities_race_condition() {
 
 #[test]
 fn entity_reuse() {
+    const COUNT: usize = u16::MAX as usize;
     let mut entities = crate::entity::Entities::default();
     let mut spawned = Vec::new();
-    for _ in 0..=crate::entity::MAX_ENTITY_ID {
+    for _ in 0..=COUNT {
         spawned.push(entities.spawn());
     }
     while let Some(i) = spawned.pop() {
-        entities.despawn(i, &mut Vec::new());
+        entities.despawn(i, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
     }
-    for _ in 0..=crate::entity::MAX_ENTITY_ID / 2 {
+    for _ in 0..=COUNT / 2 {
         spawned.push(entities.spawn());
     }
     while let Some(i) = spawned.pop() {
-        entities.despawn(i, &mut Vec::new());
+        entities.despawn(i, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
     }
-    for _ in 0..=crate::entity::MAX_ENTITY_ID / 2 {
+    for _ in 0..=COUNT / 2 {
         spawned.push(entities.spawn());
     }
     while let Some(i) = spawned.pop() {
@@ -139,21 +140,147 @@ fn entity_reuse() {
     }
 }
 
+#[test]
+fn entity_beyond_u16() {
+    let mut entities = crate::entity::Entities::default();
+    let mut last = entities.spawn();
+    for _ in 0..300_000 {
+        last = entities.spawn();
+    }
+    assert_eq!(last.id(), 300_000);
+    assert!(entities.is_alive(last));
+    entities.despawn(last, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
+    assert!(!entities.is_alive(last));
+    assert_eq!(Entity::from_bits(last.to_bits()), Some(last));
+    assert_eq!(Entity::from_bits((u32::MAX as u64) << 32 | last.id() as u64), None);
+}
+
+#[test]
+fn entity_max_id() {
+    let mut entities = crate::entity::Entities::default();
+    entities.set_highest_id(crate::entity::MAX_ENTITY_ID - 1);
+    let batch = entities.spawn_batch(1);
+    let last = entities.spawn();
+    assert_eq!(batch[0].id() as usize, crate::entity::MAX_ENTITY_ID - 1);
+    assert_eq!(last.id() as usize, crate::entity::MAX_ENTITY_ID);
+    assert!(entities.is_alive(last));
+    // the last id still gets a dense index, which the previous limit turned into none
+    assert_ne!(SparseIndex::new(last.id() as usize), SparseIndex::NONE);
+}
+
+#[test]
+#[should_panic(expected = "sparse index overflow")]
+fn sparse_index_overflow() {
+    SparseIndex::new(SparseIndex::MAX + 1);
+}
+
+#[test]
+#[should_panic(expected = "entity overflow")]
+fn entity_id_overflow() {
+    let mut entities = crate::entity::Entities::default();
+    entities.set_highest_id(crate::entity::MAX_ENTITY_ID);
+    entities.spawn();
+    entities.spawn();
+}
+
+#[test]
+#[should_panic(expected = "entity overflow")]
+fn entity_batch_id_overflow() {
+    let mut entities = crate::entity::Entities::default();
+    entities.set_highest_id(crate::entity::MAX_ENTITY_ID);
+    entities.spawn_batch(2);
+}
+
+#[test]
+fn entity_retire_exhausted_version() {
+    let mut entities = crate::entity::Entities::default();
+    let a = entities.spawn();
+    entities.despawn(a, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
+    entities.set_version(a.id(), crate::entity::MAX_ENTITY_VERSION);
+    let b = entities.spawn();
+    assert_eq!(b.id(), a.id());
+    assert_eq!(b.version(), crate::entity::MAX_ENTITY_VERSION);
+
+    entities.despawn(b, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
+    assert!(!entities.is_alive(a));
+    assert!(!entities.is_alive(b));
+
+    let c = entities.spawn();
+    assert_ne!(c.id(), b.id(), "retired slot was reused");
+    assert!(entities.is_alive(c));
+}
+
+#[test]
+fn entity_recycling_policies() {
+    use crate::entity::{Entities, EntityRecyclingPolicy};
+    let spawn_despawn = |entities: &mut Entities| {
+        let spawned: Vec<Entity> = (0..3).map(|_| entities.spawn()).collect();
+        for entity in spawned.iter() {
+            entities.despawn(*entity, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
+        }
+        spawned
+    };
+
+    let mut entities = Entities::default();
+    let [a, _, c] = spawn_despawn(&mut entities)[..] else { unreachable!() };
+    assert_eq!(entities.spawn().id(), c.id());
+
+    let mut entities = Entities::default();
+    entities.set_recycling_policy(EntityRecyclingPolicy::Fifo);
+    spawn_despawn(&mut entities);
+    assert_eq!(entities.spawn().id(), a.id());
+    assert_eq!(entities.spawn().id(), a.id() + 1);
+
+    let mut entities = Entities::default();
+    entities.set_recycling_policy(EntityRecyclingPolicy::QuarantineSpawns(4));
+    let freed = spawn_despawn(&mut entities);
+    let fresh: Vec<Entity> = (0..4).map(|_| entities.spawn()).collect();
+    assert!(fresh.iter().all(|entity| entity.version() == 0));
+    let extra = entities.spawn();
+    entities.despawn(extra, HierarchyDespawnPolicy::Recursive, &mut Vec::new());
+    let reused = entities.spawn();
+    assert_eq!(reused.id(), freed[0].id());
+    assert_eq!(reused.version(), 1);
+
+    let mut entities = Entities::default();
+    entities.set_recycling_policy(EntityRecyclingPolicy::QuarantineScheduleRuns(2));
+    spawn_despawn(&mut entities);
+    assert_eq!(entities.spawn().version(), 0);
+    entities.end_schedule_run();
+    assert_eq!(entities.spawn().version(), 0);
+    entities.end_schedule_run();
+    assert_eq!(entities.spawn().id(), a.id());
+    entities.set_recycling_policy(EntityRecyclingPolicy::Lifo);
+    assert_eq!(entities.spawn().id(), c.id());
+}
+
 struct A; impl Component for A {}
 struct B; impl Component for B {}
 struct C; impl Component for C {}
 struct D; impl Component for D {}
 
+#[test]
+fn blob_vec_zero_sized_reserve() {
+    struct Marker;
+    let mut blob_vec = crate::storage::blob_vec::BlobVec::new::<Marker>();
+    for _ in 0..4 {
+        let mut marker = Marker;
+        unsafe { blob_vec.push(std::ptr::NonNull::from(&mut marker).cast()) };
+    }
+    blob_vec.reserve(8);
+    assert_eq!(blob_vec.len(), 4);
+}
+
 #[test]
 fn query_required_forbidden() {
     let mut world = World::new(1).unwrap();
     world.spawn((A, B, C, D));
     let query = world.query_filtered::<(&A, &B), (With<D>, Without<C>)>();
-    assert_eq!(*query.required(), Bitmap::new().with_set(0).with_set(1).with_set(3));
-    assert_eq!(*query.forbidden(), Bitmap::new().with_set(2));
+    assert_eq!(*query.required(), Signature::new().with_set(0).with_set(1).with_set(3));
+    assert_eq!(*query.forbidden(), Signature::new().with_set(2));
     let query = world.query_filtered::<Entity, (With<(A, B)>, Without<(C, D)>)>();
-    assert_eq!(*query.required(), Bitmap::new().with_set(0).with_set(1));
-    assert_eq!(*query.forbidden(), Bitmap::new().with_set(2).with_set(3));
+    assert_eq!(*query.required(), Signature::new().with_set(0).with_set(1));
+    assert_eq!(*query.forbidden(), Signature::new().with_set(2).with_set(3));
 }
 
 #[test]
@@ -169,3 +296,93 @@ fn query_compatible() {
     assert!(b.get_conflict(&c).is_none());
     assert!(c.get_conflict(&d).is_none());
 }
+
+#[test]
+fn bundle_removal_moves_once() {
+    let mut world = World::new(1).unwrap();
+    let a = world.spawn((A, B, C, D));
+    world.remove_bundle::<(A, B, C)>(a);
+    assert_eq!(world.groups().len(), 2);
+    assert_eq!(world.get_entity_signature(a), Some(Signature::new().with_set(3)));
+
+    let b = world.spawn((A, B, C));
+    assert!(world.take::<(A, B)>(b).is_some());
+    assert_eq!(world.groups().len(), 4);
+    assert_eq!(world.get_entity_signature(b), Some(Signature::new().with_set(2)));
+}
+
+#[test]
+fn spawn_writes_final_table() {
+    let mut world = World::new(1).unwrap();
+    world.spawn((A, B, C));
+    world.spawn_batch([(A, B, C), (A, B, C)]);
+    // no tables for the partially written bundles on the way
+    assert_eq!(world.tables().len(), 1);
+    let table = &world.tables()[0];
+    assert_eq!(table.entities().len(), 3);
+    for component_id in 0..3 {
+        assert_eq!(table.column(component_id).map(|column| column.len()), Some(3));
+    }
+}
+
+#[test]
+fn group_transitions_cached() {
+    let mut world = World::new(1).unwrap();
+    let entities: Vec<Entity> = (0..4).map(|_| world.spawn(A)).collect();
+    for _ in 0..3 {
+        for entity in entities.iter().copied() {
+            world.set_component(entity, B);
+            world.remove_component::<A>(entity);
+            world.set_component(entity, A);
+            world.remove_component::<B>(entity);
+        }
+    }
+    assert_eq!(world.groups().len(), 3);
+    assert_eq!(world.tables().len(), 3);
+    let a = Signature::new().with_set(0);
+    let b = Signature::new().with_set(1);
+    let ab = a.clone().with_set(1);
+    assert_eq!(world.groups().get(&a).map(|group| group.len()), Some(4));
+
+    // every transition was cached on the first round and reused after it
+    let groups = world.groups();
+    let [a_group, b_group, ab_group] = [&a, &b, &ab].map(|signature| groups.get_group_id(signature).unwrap());
+    assert_eq!(groups.edges(a_group).with(1), Some(ab_group));
+    assert_eq!(groups.edges(ab_group).without(0), Some(b_group));
+    assert_eq!(groups.edges(b_group).with(0), Some(ab_group));
+    assert_eq!(groups.edges(ab_group).without(1), Some(a_group));
+    let [a_table, b_table, ab_table] = [&a, &b, &ab].map(|signature| world.get_table_id(signature).unwrap());
+    let tables = world.tables();
+    assert_eq!(tables[a_table].edges().with(1), Some(ab_table));
+    assert_eq!(tables[ab_table].edges().without(0), Some(b_table));
+    assert_eq!(tables[b_table].edges().with(0), Some(ab_table));
+    assert_eq!(tables[ab_table].edges().without(1), Some(a_table));
+
+    assert_eq!(world.query::<(&A, &B)>().iter().count(), 0);
+    assert_eq!(world.query::<&A>().iter().count(), 4);
+}
+
+#[test]
+fn change_ticks_survive_wraparound() {
+    struct Health; impl Component for Health {}
+    #[derive(Default)]
+    struct Added(usize); impl Resource for Added {}
+    let mut world = World::new(1).unwrap();
+    world.insert_resource(Added::default());
+    world.spawn(Health);
+    let mut schedule = Schedule::default();
+    schedule.add_system(|query: Query<Ref<Health>>, mut added: ResMut<Added>| {
+        added.0 += query.iter().filter(|health| health.is_added()).count();
+    });
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource::<Added>().unwrap().0, 1);
+
+    // stepping by less than the threshold, like a long running world does, until the counter wraps around twice
+    let mut tick = world.change_tick().get();
+    for _ in 0..20 {
+        tick = tick.wrapping_add(CHECK_TICK_THRESHOLD - 1);
+        world.set_change_tick(Tick::new(tick));
+        schedule.run(&mut world);
+    }
+    assert_eq!(world.get_resource::<Added>().unwrap().0, 1);
+}
@@ -1,4 +1,6 @@
-use crate::{observer::TriggerInput, world::WorldPtr, Entity};
+use std::marker::PhantomData;
+
+use crate::{observer::TriggerInput, world::WorldPtr, Component, Entity};
 
 pub struct Trigger<'a, E: Send + Sync + 'static> {
     event: &'a mut E,
@@ -25,3 +27,34 @@ impl<E: Send + Sync + 'static> Trigger<'_, E> {
         }
     }
 }
+
+/// Triggered with the entity as target after it was disabled
+pub struct OnDisable;
+
+/// Triggered with the entity as target after it was enabled
+pub struct OnEnable;
+
+/// Triggered with the entity as target after `C` was added to it
+pub struct OnAdd<C: Component>(PhantomData<C>);
+
+/// Triggered with the entity as target after `C` was added or overwritten, follows [`OnAdd`]
+pub struct OnInsert<C: Component>(PhantomData<C>);
+
+/// Triggered with the entity as target before `C` is removed from it, including on despawn
+pub struct OnRemove<C: Component>(PhantomData<C>);
+
+/// Triggered with the entity as target before it is despawned, precedes the [`OnRemove`] of its components
+pub struct OnDespawn;
+
+macro_rules! lifecycle_event_impl {
+    ($($name:ident),+) => {
+        $(impl<C: Component> $name<C> {
+            #[inline]
+            pub(crate) const fn new() -> Self {
+                Self(PhantomData)
+            }
+        })+
+    }
+}
+
+lifecycle_event_impl!(OnAdd, OnInsert, OnRemove);
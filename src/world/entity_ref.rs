@@ -0,0 +1,153 @@
+use crate::{Component, ComponentBundle, Entity, MutableComponent, Signature, World};
+
+/// Read-only access to a single alive entity
+#[derive(Clone, Copy)]
+pub struct EntityRef<'w> {
+    world: &'w World,
+    entity: Entity,
+}
+
+impl<'w> EntityRef<'w> {
+    /// Entity must be alive
+    #[inline]
+    pub(crate) const fn new(world: &'w World, entity: Entity) -> Self {
+        Self { world, entity }
+    }
+
+    #[inline]
+    pub const fn id(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    pub fn get<C: Component>(&self) -> Option<&'w C> {
+        self.world.components.get_component(self.entity)
+    }
+
+    #[inline]
+    pub fn contains<C: Component>(&self) -> bool {
+        self.get::<C>().is_some()
+    }
+
+    #[inline]
+    pub fn signature(&self) -> Signature {
+        self.world.components.get_entity_signature_by_type_id(self.entity).unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn children(&self) -> &'w [Entity] {
+        self.world.entities.children(self.entity)
+    }
+
+    #[inline]
+    pub fn parent(&self) -> Option<Entity> {
+        self.world.entities.parent(self.entity)
+    }
+}
+
+/// Mutable access to a single entity and the world it lives in.
+/// Hooks triggered by [`EntityWorldMut::insert`] or [`EntityWorldMut::remove`] can despawn the entity, later calls then do nothing.
+pub struct EntityWorldMut<'w> {
+    world: &'w mut World,
+    entity: Entity,
+}
+
+impl<'w> EntityWorldMut<'w> {
+    /// Entity must be alive
+    #[inline]
+    pub(crate) const fn new(world: &'w mut World, entity: Entity) -> Self {
+        Self { world, entity }
+    }
+
+    #[inline]
+    pub const fn id(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.world.is_alive(self.entity)
+    }
+
+    #[inline]
+    pub fn get<C: Component>(&self) -> Option<&C> {
+        self.world.get_component(self.entity)
+    }
+
+    #[inline]
+    pub fn get_mut<C: MutableComponent>(&mut self) -> Option<&mut C> {
+        self.world.get_component_mut(self.entity)
+    }
+
+    #[inline]
+    pub fn contains<C: Component>(&self) -> bool {
+        self.get::<C>().is_some()
+    }
+
+    #[inline]
+    pub fn signature(&self) -> Signature {
+        self.world.get_entity_signature(self.entity).unwrap_or_default()
+    }
+
+    /// Adds the bundle's components, replacing the ones the entity already has
+    pub fn insert<B: ComponentBundle>(&mut self, bundle: B) -> &mut Self {
+        if self.is_alive() {
+            bundle.insert(self.entity, self.world);
+        }
+        self
+    }
+
+    /// Adds the bundle's components the entity doesn't have yet
+    pub fn insert_if_new<B: ComponentBundle>(&mut self, bundle: B) -> &mut Self {
+        if self.is_alive() {
+            bundle.insert_if_new(self.entity, self.world);
+        }
+        self
+    }
+
+    pub fn remove<C: Component>(&mut self) -> &mut Self {
+        self.world.remove_component::<C>(self.entity);
+        self
+    }
+
+    /// Removes the bundle's components the entity has
+    pub fn remove_bundle<B: ComponentBundle>(&mut self) -> &mut Self {
+        self.world.remove_bundle::<B>(self.entity);
+        self
+    }
+
+    /// Removes and returns the bundle if the entity has all of its components
+    pub fn take<B: ComponentBundle>(&mut self) -> Option<B> {
+        self.world.take::<B>(self.entity)
+    }
+
+    #[inline]
+    pub fn children(&self) -> &[Entity] {
+        self.world.children(self.entity)
+    }
+
+    #[inline]
+    pub fn parent(&self) -> Option<Entity> {
+        self.world.parent(self.entity)
+    }
+
+    pub fn add_child(&mut self, child: Entity) -> &mut Self {
+        self.world.add_child(self.entity, child);
+        self
+    }
+
+    /// Despawns the entity, its children are handled according to the world's [`crate::HierarchyDespawnPolicy`]
+    pub fn despawn(self) {
+        self.world.despawn(self.entity);
+    }
+
+    #[inline]
+    pub fn world(&self) -> &World {
+        self.world
+    }
+
+    #[inline]
+    pub fn into_world_mut(self) -> &'w mut World {
+        self.world
+    }
+}
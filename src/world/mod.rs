@@ -0,0 +1,1278 @@
+mod entity_ref;
+pub use entity_ref::{EntityRef, EntityWorldMut};
+
+use std::{any::TypeId, marker::PhantomData, ops::{Deref, DerefMut}, ptr::{self, NonNull}, sync::atomic::{AtomicU32, Ordering}};
+
+use crate::{access::Conflict, component::{Components, LifecycleEvent, RequiredComponent}, error::{ECSError, ErrorHandlerInput}, observer::{ObserverInput, Observers, TriggerInput}, query::QueryData, resource::{Changed, ResourceId}, schedule::Schedules, storage::table::Table, system::{IntoSystem, System, SystemId, error::InternalSystemError}, *};
+
+static WORLD_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct WorldId(usize);
+
+pub struct World {
+    id: WorldId,
+    components: component::Components,
+    pub(crate) entities: entity::Entities,
+    relations: relationship::Relations,
+    resources: resource::Resources,
+    observers: Observers,
+    schedules: Schedules,
+    pub(crate) thread_pool: rayon::ThreadPool,
+    command_buffer: Vec<u8>,
+    hierarchy_despawn_policy: HierarchyDespawnPolicy,
+    change_tick: AtomicU32,
+    // change tick the stored ticks were last clamped at
+    last_check_tick: Tick,
+    error_handler: Box<dyn System<Input = ECSError>>,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_THREAD_COUNT).unwrap()
+    }
+}
+
+unsafe impl Sync for World {}
+unsafe impl Send for World {}
+
+impl World {
+    const DEFAULT_THREAD_COUNT: usize = 16;
+    pub fn new(num_threads: usize) -> Result<Self, rayon::ThreadPoolBuildError> {
+        let mut world = Self {
+            id: WorldId(WORLD_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)),
+            components: Default::default(),
+            entities: Default::default(),
+            relations: Default::default(),
+            resources: Default::default(),
+            observers: Default::default(),
+            schedules: Schedules::default(),
+            thread_pool: rayon::ThreadPoolBuilder::new().num_threads(num_threads).build()?,
+            command_buffer: Vec::new(),
+            hierarchy_despawn_policy: HierarchyDespawnPolicy::default(),
+            change_tick: AtomicU32::new(1),
+            last_check_tick: Tick::default(),
+            error_handler: Box::new(crate::error::handlers::panic_error_handler.into_system()),
+        };
+        let mut world_ptr = world.world_ptr_mut();
+        unsafe { world_ptr.as_world_mut() }.error_handler.init(&mut world).unwrap();
+        Ok(world)
+    }
+
+    #[inline]
+    pub const fn id(&self) -> WorldId {
+        self.id
+    }
+
+    /// Tick that component writes made outside of systems are stamped with
+    #[inline]
+    pub fn change_tick(&self) -> Tick {
+        Tick::new(self.change_tick.load(Ordering::Relaxed))
+    }
+
+    /// Returns the tick of the system run that is starting
+    #[inline]
+    pub(crate) fn increment_change_tick(&self) -> Tick {
+        Tick::new(self.change_tick.fetch_add(1, Ordering::Relaxed))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_change_tick(&self, tick: Tick) {
+        self.change_tick.store(tick.get(), Ordering::Relaxed);
+    }
+
+    /// Clamps the ticks of components, observers and the error handler once every [`CHECK_TICK_THRESHOLD`] ticks,
+    /// so that old changes don't look new again after the counter wraps around. Schedules clamp their systems when run.
+    pub(crate) fn check_change_ticks(&mut self) {
+        let change_tick = self.change_tick();
+        if !change_tick.needs_check(self.last_check_tick) { return; }
+        self.components.check_change_ticks(change_tick);
+        self.observers.check_change_ticks(change_tick);
+        self.error_handler.check_change_tick(change_tick);
+        self.last_check_tick = change_tick;
+    }
+
+    #[inline]
+    pub const fn world_ptr<'a>(&self) -> WorldPtr<'a> {
+        WorldPtr {
+            ptr: NonNull::new(ptr::from_ref(self).cast_mut()).expect("world pointer cast null"),
+            allow_mutable_access: false,
+            _m: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub const fn world_ptr_mut<'a>(&mut self) -> WorldPtr<'a> {
+        WorldPtr {
+            ptr: NonNull::new(ptr::from_mut(self)).expect("world pointer cast null"),
+            allow_mutable_access: true,
+            _m: PhantomData,
+        }
+    }
+
+    
+    // ===== Schedules =====
+
+
+    pub fn run_schedule<L: schedule::ScheduleLabel>(&mut self, label: L) {
+        let mut world_ptr = self.world_ptr_mut();
+        let world = unsafe { world_ptr.as_world_mut() };
+        let Some(schedule) = world.schedules.get_mut(&label) else { return; };
+        schedule.run(unsafe { world_ptr.as_world_mut() });
+    }
+
+    #[inline]
+    pub fn insert_schedule<L: schedule::ScheduleLabel>(&mut self, label: L, schedule: Schedule) {
+        self.schedules.insert(label, schedule);
+    }
+
+    #[inline]
+    pub fn remove_schedule<L: schedule::ScheduleLabel>(&mut self, label: &L) -> Option<Schedule> {
+        self.schedules.remove(label)
+    }
+
+
+    // ===== Resources =====
+    
+
+    #[inline]
+    pub fn register_resource<R: Resource>(&mut self) -> ResourceId {
+        self.resources.register::<R>()
+    }
+
+    #[inline]
+    pub fn insert_resource<R: Resource>(&mut self, mut resource: R) -> Option<R> {
+        resource.on_add(&mut self.command_buffer());
+        match self.resources.insert(resource) {
+            Some(mut res) => {
+                res.on_remove(&mut self.command_buffer());
+                self.process_command_buffer();
+                Some(res)
+            },
+            None => {
+                self.process_command_buffer();
+                None
+            }
+        }
+    }
+
+    #[inline]
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        if let Some(mut res) = self.resources.remove::<R>() {
+            res.on_remove(&mut self.command_buffer());
+            self.process_command_buffer();
+            return Some(res);
+        }
+        None
+    }
+
+    #[inline]
+    pub(crate) fn remove_resource_by_id(&mut self, resource_id: ResourceId) {
+        let mut commands = Commands::new(&mut self.command_buffer, &self.entities);
+        self.resources.remove_by_id(resource_id, &mut commands);
+        self.process_command_buffer();
+    }
+
+    #[inline]
+    pub fn get_resource<R: Resource>(&self) -> Option<&R> {
+        self.resources.get::<R>()
+    }
+
+    #[inline]
+    pub fn get_resource_mut<'a, R: Resource>(&mut self) -> Option<WorldResMut<'a, R>> {
+        let mut world_ptr = self.world_ptr_mut();
+        let val = unsafe { world_ptr.as_world_mut() }.resources.get_mut::<R>()?;
+        Some(WorldResMut {
+            val,
+            world_ptr,
+            was_modified: false
+        })
+    }
+
+    #[inline]
+    pub(crate) fn get_resource_ref_mut<R: Resource>(&mut self) -> Option<&'_ mut R> {
+        self.resources.get_mut()
+    }
+
+    #[inline]
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.resources.get()
+            .unwrap_or_else(|| panic!("resource '{}' not initialized", std::any::type_name::<R>()))
+    }
+
+    #[inline]
+    pub fn resource_mut<'a, R: Resource>(&mut self) -> WorldResMut<'a, R> {
+        let mut world_ptr = self.world_ptr_mut();
+        let val = unsafe { world_ptr.as_world_mut() }.resource_ref_mut::<R>();
+        WorldResMut {
+            val,
+            world_ptr,
+            was_modified: false
+        }
+    }
+
+    #[inline]
+    pub(crate) fn resource_ref_mut<R: Resource>(&mut self) -> &mut R {
+        self.resources.get_mut()
+            .unwrap_or_else(|| panic!("resource '{}' not initialized", std::any::type_name::<R>()))
+    }
+
+    #[inline]
+    pub fn get_resource_or_insert_with<'a, R: Resource, F: FnOnce() -> R>(&mut self, f: F) -> WorldResMut<'a, R> {
+        let mut world_ptr = self.world_ptr_mut();
+        let mut commands = self.command_buffer();
+        let val = unsafe { world_ptr.as_world_mut() }.resources.get_or_insert_with(|| {
+            let mut resource = f();
+            resource.on_add(&mut commands);
+            resource
+        });
+        self.process_command_buffer();
+        WorldResMut {
+            val,
+            world_ptr,
+            was_modified: false
+        }
+    }
+
+    #[inline]
+    pub(crate) fn get_resource_ref_or_insert_with<R: Resource, F: FnOnce() -> R>(&mut self, f: F) -> &mut R {
+        let mut world_ptr = self.world_ptr_mut();
+        let mut commands = self.command_buffer();
+        unsafe { world_ptr.as_world_mut() }.resources.get_or_insert_with(|| {
+            let mut resource = f();
+            resource.on_add(&mut commands);
+            resource
+        })
+    }
+
+    #[inline]
+    pub fn get_resource_id<R: Resource>(&self) -> Option<ResourceId> {
+        self.resources.get_resource_id::<R>()
+    }
+
+    #[inline]
+    pub fn resource_id<R: Resource>(&self) -> ResourceId {
+        self.resources.get_resource_id::<R>()
+            .unwrap_or_else(|| panic!("resource '{}' not identified", std::any::type_name::<R>()))
+    }
+
+    #[inline]
+    pub fn get_resource_by_id<R: Resource>(&self, id: ResourceId) -> Option<&R> {
+        self.resources.get_resource_by_id(id)
+    }
+
+    #[inline]
+    pub fn get_resource_by_id_mut<R: Resource>(&mut self, id: ResourceId) -> Option<&mut R> {
+        self.resources.get_resource_by_id_mut(id)
+    }
+
+    #[inline]
+    pub fn resource_by_id<R: Resource>(&self, id: ResourceId) -> &R {
+        self.resources.get_resource_by_id(id)
+            .unwrap_or_else(|| panic!("resource '{}' not initialized", std::any::type_name::<R>()))
+    }
+
+    #[inline]
+    pub fn resource_by_id_mut<R: Resource>(&mut self, id: ResourceId) -> &mut R {
+        self.resources.get_resource_by_id_mut(id)
+            .unwrap_or_else(|| panic!("resource '{}' not initialized", std::any::type_name::<R>()))
+    }
+
+
+    // ===== Components =====
+
+
+    #[inline]
+    pub fn register_component<C: Component>(&mut self) -> ComponentId {
+        self.components.register_component::<C>()
+    }
+
+    /// Lets [`World::clone_entity`] copy the component
+    pub fn register_component_clone<C: Component + Clone>(&mut self) {
+        let component_id = self.register_component::<C>();
+        self.components.set_clone_fn(component_id, |world, _, source, target, _| {
+            let Some(component) = world.get_component::<C>(source) else { return; };
+            let component = component.clone();
+            world.set_component(target, component);
+        });
+    }
+
+    /// Lets [`World::clone_entity`] copy the component, remapping the entities it references to their copies
+    pub fn register_component_clone_mapped<C: Component + Clone + MapEntities>(&mut self) {
+        let component_id = self.register_component::<C>();
+        self.components.set_clone_fn(component_id, |world, _, source, target, map| {
+            let Some(component) = world.get_component::<C>(source) else { return; };
+            let mut component = component.clone();
+            component.map_entities(map);
+            world.set_component(target, component);
+        });
+    }
+
+    /// Sets the hooks of the component, registering it if absent
+    pub fn register_component_hooks<C: Component>(&mut self) -> &mut ComponentHooks {
+        let component_id = self.register_component::<C>();
+        self.components.hooks_mut(component_id)
+    }
+
+    #[inline]
+    pub fn set_component<C: Component>(&mut self, entity: Entity, component: C) {
+        if !self.is_alive(entity) { return; }
+        let component_id = self.register_component::<C>();
+        let tick = self.change_tick();
+        let replaced = self.write_component(entity, component_id, component, |components, component| components.set_component(entity, component, tick));
+        self.insert_required_components(entity, component_id);
+        self.trigger_inserted::<C>(entity, component_id, !replaced);
+    }
+
+    // inserts the defaults of the component's requirements the entity is missing
+    fn insert_required_components(&mut self, entity: Entity, component_id: ComponentId) {
+        for index in 0..self.components.required_components(component_id).len() {
+            if !self.is_alive(entity) { return; }
+            let required = self.components.required_components(component_id)[index];
+            if !self.components.contains(entity, required.id()) {
+                required.insert(self, entity);
+            }
+        }
+    }
+
+    /// # Safety
+    /// Entity must be alive and spawned under a signature containing the required components
+    pub(crate) unsafe fn write_required_components(&mut self, entity: Entity, required: &[RequiredComponent]) {
+        for required in required {
+            unsafe { required.write(self, entity) };
+        }
+    }
+
+    /// Writes a component of a spawned entity, its hooks run once the whole bundle is written by [`World::finish_spawn`]
+    /// # Safety
+    /// Entity must be alive and spawned under a signature containing the component
+    #[inline]
+    pub(crate) unsafe fn write_spawned_component<C: Component>(&mut self, entity: Entity, mut component: C) {
+        let tick = self.change_tick();
+        component.on_add(&mut self.command_buffer());
+        if let Some(mut replaced) = unsafe { self.components.write_spawned(entity, component, tick) } {
+            replaced.on_remove(&mut self.command_buffer());
+        }
+    }
+
+    /// Runs the add and insert hooks of a spawned entity's components once all of them are written, then triggers the observers of its required components
+    pub(crate) fn finish_spawn(&mut self, entity: Entity, required: &[RequiredComponent]) {
+        let Some(signature) = self.get_entity_signature(entity) else { return; };
+        let mut commands = Commands::new(&mut self.command_buffer, &self.entities);
+        for component_id in signature.iter_ones().map(ComponentId::new) {
+            let hooks = self.components.hooks(component_id);
+            let context = HookContext { entity, component_id };
+            hooks.run_add(&mut commands, context);
+            hooks.run_insert(&mut commands, context);
+        }
+        self.process_command_buffer();
+        for required in required {
+            required.trigger_added(self, entity);
+        }
+    }
+
+    // runs the component hooks around `write`, which returns the replaced value
+    #[inline]
+    fn write_component<C: Component, F: FnOnce(&mut Components, C) -> Option<C>>(&mut self, entity: Entity, component_id: ComponentId, mut component: C, write: F) -> bool {
+        let hooks = self.components.hooks(component_id);
+        let context = HookContext { entity, component_id };
+        let replaced = self.components.contains(entity, component_id);
+        if replaced {
+            hooks.run_replace(&mut self.command_buffer(), context);
+        }
+        component.on_add(&mut self.command_buffer());
+        if let Some(mut component) = write(&mut self.components, component) {
+            component.on_remove(&mut self.command_buffer());
+        }
+        if !replaced {
+            hooks.run_add(&mut self.command_buffer(), context);
+        }
+        hooks.run_insert(&mut self.command_buffer(), context);
+        self.process_command_buffer();
+        replaced
+    }
+
+    #[inline]
+    pub fn remove_component<C: Component>(&mut self, entity: Entity) {
+        if !self.is_alive(entity) { return; }
+        let Some(component_id) = self.components.get_component_id::<C>() else { return; };
+        if self.components.contains(entity, component_id) && self.components.is_observed(component_id, LifecycleEvent::Remove, &self.observers) {
+            self.trigger_lifecycle(OnRemove::<C>::new(), entity);
+            if !self.is_alive(entity) { return; }
+        }
+        if let Some(mut component) = self.components.remove_component::<C>(entity) {
+            let hooks = self.components.hooks(component_id);
+            let context = HookContext { entity, component_id };
+            hooks.run_replace(&mut self.command_buffer(), context);
+            component.on_remove(&mut self.command_buffer());
+            hooks.run_remove(&mut self.command_buffer(), context);
+        }
+        self.process_command_buffer();
+    }
+
+    /// Removes the bundle's components the entity has, moving it between groups once
+    pub fn remove_bundle<B: component::ComponentBundle>(&mut self, entity: Entity) {
+        if !self.is_alive(entity) { return; }
+        let signature = B::signature(self);
+        self.remove_signature(entity, &signature);
+    }
+
+    fn remove_signature(&mut self, entity: Entity, signature: &Signature) {
+        if !self.trigger_removed(entity, signature) { return; }
+        let mut commands = Commands::new(&mut self.command_buffer, &self.entities);
+        self.components.remove_signature(entity, signature, Some(&mut commands));
+        self.process_command_buffer();
+    }
+
+    /// Removes and returns the bundle if the entity has all of its components, moving it between groups once
+    pub fn take<B: component::ComponentBundle>(&mut self, entity: Entity) -> Option<B> {
+        if !self.is_alive(entity) { return None; }
+        let signature = B::signature(self);
+        let contains_bundle = |world: &Self| world.get_entity_signature(entity).is_some_and(|entity_signature| signature.is_subset(&entity_signature));
+        if !contains_bundle(self) { return None; }
+        if !self.trigger_removed(entity, &signature) || !contains_bundle(self) { return None; }
+        let bundle = unsafe { B::take_components(entity, self) };
+        self.components.remove_signature(entity, &signature, None);
+        self.process_command_buffer();
+        Some(bundle)
+    }
+
+    /// # Safety
+    /// Entity must be alive and have the component, which must then be removed from it with [`Components::remove_signature`] without commands
+    pub(crate) unsafe fn take_component_value<C: Component>(&mut self, entity: Entity) -> C {
+        let component_id = unsafe { self.components.get_component_id::<C>().unwrap_unchecked() };
+        let mut component = unsafe { self.components.take_value::<C>(entity, component_id) }.expect("world take_component_value component missing");
+        let hooks = self.components.hooks(component_id);
+        let context = HookContext { entity, component_id };
+        hooks.run_replace(&mut self.command_buffer(), context);
+        component.on_remove(&mut self.command_buffer());
+        hooks.run_remove(&mut self.command_buffer(), context);
+        component
+    }
+
+    /// Keeps a [`ComponentIndex`] of the component, looked up through [`World::index`] or the [`Index`] param
+    #[inline]
+    pub fn register_component_index<C: IndexableComponent>(&mut self) -> ComponentId {
+        self.components.register_index::<C>()
+    }
+
+    /// Index of the component, reindexing the values changed in place first
+    #[inline]
+    pub fn index<C: IndexableComponent>(&mut self) -> &ComponentIndex<C> {
+        self.refresh_indexes();
+        self.components.index::<C>()
+            .unwrap_or_else(|| panic!("index of '{}' not registered", std::any::type_name::<C>()))
+    }
+
+    #[inline]
+    pub(crate) fn refresh_indexes(&mut self) {
+        let change_tick = self.change_tick();
+        self.components.refresh_indexes(change_tick);
+    }
+
+    /// # Safety
+    /// Component_id must correspond to the component C
+    #[inline]
+    pub(crate) unsafe fn index_by_id<C: IndexableComponent>(&self, component_id: ComponentId) -> &ComponentIndex<C> {
+        unsafe { self.components.index_by_id::<C>(component_id) }
+            .unwrap_or_else(|| panic!("index of '{}' not registered", std::any::type_name::<C>()))
+    }
+
+    /// Registers a component without a Rust type, every call registers a new one
+    #[inline]
+    pub fn register_dynamic_component(&mut self, descriptor: ComponentDescriptor) -> ComponentId {
+        self.components.register_dynamic_component(descriptor)
+    }
+
+    #[inline]
+    pub fn component_name(&self, component_id: ComponentId) -> Option<&str> {
+        self.components.name(component_id)
+    }
+
+    /// Moves the value into the entity's component, dropping it instead if the entity is dead
+    /// # Safety
+    /// Value must point to a valid value of the component, which is moved out and must not be used afterwards
+    pub unsafe fn insert_by_id(&mut self, entity: Entity, component_id: ComponentId, value: PtrMut<'_>) {
+        assert!(component_id.get() < self.components.len(), "component id {} not registered", component_id.get());
+        if !self.is_alive(entity) {
+            unsafe { self.components.drop_value(component_id, value) };
+            return;
+        }
+        let hooks = self.components.hooks(component_id);
+        let context = HookContext { entity, component_id };
+        let replaced = self.components.contains(entity, component_id);
+        if replaced {
+            hooks.run_replace(&mut self.command_buffer(), context);
+        }
+        (self.components.on_add(component_id))(value, &mut self.command_buffer());
+        let tick = self.change_tick();
+        if unsafe { self.components.insert_by_id(entity, component_id, value, tick) } {
+            (self.components.on_remove(component_id))(value, &mut self.command_buffer());
+            unsafe { self.components.drop_value(component_id, value) };
+        }
+        if !replaced {
+            hooks.run_add(&mut self.command_buffer(), context);
+        }
+        hooks.run_insert(&mut self.command_buffer(), context);
+        self.process_command_buffer();
+        self.insert_required_components(entity, component_id);
+        if !replaced && self.components.is_observed(component_id, LifecycleEvent::Add, &self.observers) {
+            (self.components.lifecycle_trigger(component_id, LifecycleEvent::Add))(self, entity);
+        }
+        if self.components.is_observed(component_id, LifecycleEvent::Insert, &self.observers) {
+            (self.components.lifecycle_trigger(component_id, LifecycleEvent::Insert))(self, entity);
+        }
+    }
+
+    #[inline]
+    pub fn get_by_id(&self, entity: Entity, component_id: ComponentId) -> Option<Ptr<'_>> {
+        if !self.is_alive(entity) || component_id.get() >= self.components.len() { return None; }
+        self.components.get_ptr_by_id(entity, component_id)
+    }
+
+    /// Marks the component changed, panics if the component is [`Immutable`]
+    #[inline]
+    pub fn get_by_id_mut(&mut self, entity: Entity, component_id: ComponentId) -> Option<PtrMut<'_>> {
+        if !self.is_alive(entity) || component_id.get() >= self.components.len() { return None; }
+        self.assert_mutable(component_id);
+        let tick = self.change_tick();
+        let (ptr, ticks) = self.components.get_mut_ptr_with_ticks_by_id(entity, component_id)?;
+        ticks.changed = tick;
+        Some(ptr)
+    }
+
+    // by id access can't rule out Immutable components at compile time
+    #[inline]
+    pub(crate) fn assert_mutable(&self, component_id: ComponentId) {
+        assert!(self.components.is_mutable(component_id), "component '{}' is immutable", self.components.name(component_id).unwrap_or_default());
+    }
+
+    pub fn remove_by_id(&mut self, entity: Entity, component_id: ComponentId) {
+        if !self.is_alive(entity) || component_id.get() >= self.components.len() { return; }
+        self.remove_signature(entity, &Signature::new().with_set(component_id.get()));
+    }
+
+    // copies a dynamic component through its descriptor's clone function
+    pub(crate) fn clone_component_by_id(&mut self, component_id: ComponentId, source: Entity, target: Entity) {
+        let Some(clone_fn) = self.components.raw_clone_fn(component_id) else { return; };
+        let Some(value) = self.get_by_id(source, component_id) else { return; };
+        let layout = self.components.layout(component_id);
+        let buffer = match layout.size() {
+            0 => ptr::without_provenance_mut::<u8>(layout.align()),
+            _ => unsafe { std::alloc::alloc(layout) },
+        };
+        let Some(buffer) = NonNull::new(buffer) else { std::alloc::handle_alloc_error(layout) };
+        unsafe {
+            clone_fn(value, PtrMut::new(buffer));
+            self.insert_by_id(target, component_id, PtrMut::new(buffer));
+            if layout.size() > 0 {
+                std::alloc::dealloc(buffer.as_ptr(), layout);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn get_component<C: Component>(&self, entity: Entity) -> Option<&C> {
+        if !self.is_alive(entity) { return None; }
+        self.components.get_component(entity)
+    }
+
+    #[inline]
+    pub fn get_component_mut<C: MutableComponent>(&mut self, entity: Entity) -> Option<&mut C> {
+        if !self.is_alive(entity) { return None; }
+        let tick = self.change_tick();
+        self.components.get_mut_component(entity, tick)
+    }
+
+    /// When the component was added to the entity and last changed
+    #[inline]
+    pub fn get_component_ticks<C: Component>(&self, entity: Entity) -> Option<ComponentTicks> {
+        if !self.is_alive(entity) { return None; }
+        self.components.get_component_ticks(entity, self.components.get_component_id::<C>()?)
+    }
+
+    /// # Safety
+    /// Entity must be alive
+    /// Component_id must correspond to a component array of type C
+    #[inline]
+    pub(crate) unsafe fn get_component_with_ticks_by_id<C: Component>(&self, entity: Entity, component_id: ComponentId) -> Option<(&C, &ComponentTicks)> {
+        unsafe { self.components.get_component_with_ticks_by_id(entity, component_id) }
+    }
+
+    /// # Safety
+    /// Entity must be alive
+    /// Component_id must correspond to a component array of type C
+    #[inline]
+    pub(crate) unsafe fn get_component_with_ticks_by_id_mut<C: MutableComponent>(&mut self, entity: Entity, component_id: ComponentId) -> Option<(&mut C, &mut ComponentTicks)> {
+        unsafe { self.components.get_mut_component_with_ticks_by_id(entity, component_id) }
+    }
+
+    /// Entity must be alive
+    #[inline]
+    pub(crate) fn get_component_ticks_by_id(&self, entity: Entity, component_id: ComponentId) -> Option<ComponentTicks> {
+        self.components.get_component_ticks(entity, component_id)
+    }
+
+    /// # Safety
+    /// Entity must be alive
+    /// Component_id must correspond to a component array of type C
+    #[inline]
+    pub unsafe fn get_component_by_id<C: Component>(&self, entity: Entity, component_id: ComponentId) -> Option<&C> {
+        if !self.is_alive(entity) { return None; }
+        unsafe { self.components.get_component_by_id(entity, component_id) }
+    }
+
+    /// # Safety
+    /// Entity must be alive
+    /// Component_id must correspond to a component array of type C
+    #[inline]
+    pub unsafe fn get_component_by_id_unchecked<C: Component>(&self, entity: Entity, component_id: ComponentId) -> &C {
+        unsafe { self.components.get_component_by_id_unchecked(entity, component_id) }
+    }
+
+    /// # Safety
+    /// Entity must be alive
+    /// Component_id must correspond to a component array of type C
+    #[inline]
+    pub unsafe fn get_component_by_id_mut<C: MutableComponent>(&mut self, entity: Entity, component_id: ComponentId) -> Option<&mut C> {
+        if !self.is_alive(entity) { return None; }
+        let tick = self.change_tick();
+        unsafe { self.components.get_mut_component_by_id(entity, component_id, tick) }
+    }
+
+    /// # Safety
+    /// Entity must be alive
+    /// Component_id must correspond to a component array of type C
+    #[inline]
+    pub unsafe fn get_component_by_id_unchecked_mut<C: MutableComponent>(&mut self, entity: Entity, component_id: ComponentId) -> &mut C {
+        let tick = self.change_tick();
+        unsafe { self.components.get_mut_component_by_id_unchecked(entity, component_id, tick) }
+    }
+
+    #[inline]
+    pub fn groups(&self) -> &Groups {
+        self.components.groups()
+    }
+
+    #[inline]
+    pub(crate) fn tables(&self) -> &[Table] {
+        self.components.tables()
+    }
+
+    #[inline]
+    pub(crate) fn tables_mut(&mut self) -> &mut [Table] {
+        self.components.tables_mut()
+    }
+
+    /// Components stored in tables
+    #[inline]
+    pub(crate) fn table_components(&self) -> &Signature {
+        self.components.table_components()
+    }
+
+    /// Whether the entity has the component, which must be stored in a sparse set
+    #[inline]
+    pub(crate) fn sparse_contains(&self, entity: Entity, component_id: usize) -> bool {
+        self.components.sparse_contains(entity, component_id)
+    }
+
+    // for testing purposes
+    #[cfg(test)]
+    #[inline]
+    pub(crate) fn get_table_id(&self, signature: &Signature) -> Option<usize> {
+        self.components.get_table_id(signature)
+    }
+
+    #[inline]
+    pub fn get_entity_signature(&self, entity: Entity) -> Option<Signature> {
+        if !self.is_alive(entity) { return None; }
+        self.components.get_entity_signature_by_type_id(entity)
+    }
+
+    #[inline]
+    pub fn get_component_signature_by_type_id(&self, type_id: &TypeId) -> Option<Signature> {
+        self.components.get_component_signature(type_id)
+    }
+
+    #[inline]
+    pub fn get_component_id<C: Component>(&self) -> Option<ComponentId> {
+        self.components.get_component_id::<C>()
+    }
+
+    #[inline]
+    pub fn component_id<C: Component>(&self) -> ComponentId {
+        self.components.get_component_id::<C>()
+            .unwrap_or_else(|| panic!("component '{}' not identified", std::any::type_name::<C>()))
+    }
+
+
+    // ===== Entities =====
+
+
+    #[inline]
+    pub fn spawn<B: component::ComponentBundle>(&mut self, components: B) -> Entity {
+        let entity = self.entities.spawn();
+        components.spawn(entity, self);
+        entity
+    }
+
+    #[inline]
+    pub(crate) fn spawn_reserved<B: component::ComponentBundle>(&mut self, entity: Entity, components: B) {
+        components.spawn(entity, self)
+    }
+
+    /// Spawns an entity for every bundle, registering the bundle and growing storages once
+    pub fn spawn_batch<B: component::ComponentBundle, I: IntoIterator<Item = B>>(&mut self, bundles: I) -> Vec<Entity> {
+        let bundles: Vec<B> = bundles.into_iter().collect();
+        let entities = self.entities.spawn_batch(bundles.len());
+        self.spawn_batch_reserved(&entities, bundles);
+        entities
+    }
+
+    pub(crate) fn spawn_batch_reserved<B: component::ComponentBundle>(&mut self, entities: &[Entity], bundles: Vec<B>) {
+        debug_assert_eq!(entities.len(), bundles.len());
+        let mut signature = B::signature(self);
+        let required = self.components.missing_required_components(&signature);
+        for required in required.iter() {
+            signature.set(required.id().get());
+        }
+        B::reserve(self, bundles.len());
+        unsafe { self.components.insert_empty_entities(entities, signature) };
+        for (entity, bundle) in entities.iter().copied().zip(bundles) {
+            unsafe {
+                bundle.write_components(entity, self);
+                self.write_required_components(entity, &required);
+            }
+        }
+        for entity in entities.iter().copied() {
+            self.finish_spawn(entity, &required);
+            B::trigger_added(entity, self);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn reserve_components<C: Component>(&mut self, additional: usize) {
+        self.components.reserve::<C>(additional);
+    }
+
+    /// Groups the entity under the signature extended with its missing required components, which are returned to be written by the caller
+    #[inline]
+    pub(crate) unsafe fn insert_empty_entity(&mut self, entity: Entity, mut signature: Signature) -> Vec<RequiredComponent> {
+        let required = self.components.missing_required_components(&signature);
+        for required in required.iter() {
+            signature.set(required.id().get());
+        }
+        unsafe { self.components.insert_empty_entity(entity, signature);}
+        required
+    }
+
+    /// Despawns the entity, its children are handled according to the world's [`HierarchyDespawnPolicy`]
+    #[inline]
+    pub fn despawn(&mut self, entity: Entity) {
+        self.despawn_with_policy(entity, self.hierarchy_despawn_policy);
+    }
+
+    /// Despawns the entity together with all of its descendants
+    #[inline]
+    pub fn despawn_recursive(&mut self, entity: Entity) {
+        self.despawn_with_policy(entity, HierarchyDespawnPolicy::Recursive);
+    }
+
+    /// Despawns the entity, its children become roots
+    #[inline]
+    pub fn despawn_detach_children(&mut self, entity: Entity) {
+        self.despawn_with_policy(entity, HierarchyDespawnPolicy::DetachChildren);
+    }
+
+    /// Despawns the entity, its children are moved to its parent
+    #[inline]
+    pub fn despawn_reparent_children(&mut self, entity: Entity) {
+        self.despawn_with_policy(entity, HierarchyDespawnPolicy::ReparentChildren);
+    }
+
+    #[inline]
+    pub const fn entity_recycling_policy(&self) -> EntityRecyclingPolicy {
+        self.entities.recycling_policy()
+    }
+
+    #[inline]
+    pub fn set_entity_recycling_policy(&mut self, policy: EntityRecyclingPolicy) {
+        self.entities.set_recycling_policy(policy);
+    }
+
+    #[inline]
+    pub const fn hierarchy_despawn_policy(&self) -> HierarchyDespawnPolicy {
+        self.hierarchy_despawn_policy
+    }
+
+    /// Sets the policy used by [`World::despawn`] and [`Commands::despawn`]
+    #[inline]
+    pub fn set_hierarchy_despawn_policy(&mut self, policy: HierarchyDespawnPolicy) {
+        self.hierarchy_despawn_policy = policy;
+    }
+
+    pub fn despawn_with_policy(&mut self, entity: Entity, policy: HierarchyDespawnPolicy) {
+        if self.entities.is_alive(entity) {
+            self.trigger_despawned(entity);
+        }
+        if self.entities.is_alive(entity) {
+            self.entities.despawn(entity, policy, &mut self.command_buffer);
+            self.relations.despawn(entity, &mut Commands::new(&mut self.command_buffer, &self.entities));
+            self.components.despawn(entity, Commands::new(&mut self.command_buffer, &self.entities));
+        }
+        self.process_command_buffer();
+    }
+
+    #[inline]
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    /// # Panics
+    /// If the entity is dead
+    #[inline]
+    pub fn entity(&self, entity: Entity) -> EntityRef<'_> {
+        self.get_entity(entity).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// # Panics
+    /// If the entity is dead
+    #[inline]
+    pub fn entity_mut(&mut self, entity: Entity) -> EntityWorldMut<'_> {
+        self.get_entity_mut(entity).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    #[inline]
+    pub fn get_entity(&self, entity: Entity) -> Result<EntityRef<'_>, EntityError> {
+        if !self.is_alive(entity) {
+            return Err(EntityError::dead(entity));
+        }
+        Ok(EntityRef::new(self, entity))
+    }
+
+    #[inline]
+    pub fn get_entity_mut(&mut self, entity: Entity) -> Result<EntityWorldMut<'_>, EntityError> {
+        if !self.is_alive(entity) {
+            return Err(EntityError::dead(entity));
+        }
+        Ok(EntityWorldMut::new(self, entity))
+    }
+
+    /// Spawns a copy of the entity with its clone registered components.
+    /// Entity references pointing at the entity itself are remapped to the copy.
+    pub fn clone_entity(&mut self, entity: Entity) -> Option<Entity> {
+        if !self.is_alive(entity) { return None; }
+        let map = self.clone_entities(&[entity]);
+        map.get_mapped(entity)
+    }
+
+    /// Spawns a copy of the entity and its whole subtree, the copy keeps no parent.
+    /// Entity references pointing into the subtree are remapped to the copies.
+    pub fn clone_entity_recursive(&mut self, entity: Entity) -> Option<Entity> {
+        if !self.is_alive(entity) { return None; }
+        let subtree: Vec<Entity> = std::iter::once(entity).chain(self.descendants(entity)).collect();
+        let map = self.clone_entities(&subtree);
+        for source in subtree.iter().copied() {
+            let parent = map.get(source);
+            let children: Vec<Entity> = self.children(source).iter().map(|child| map.get(*child)).collect();
+            for child in children {
+                self.add_child(parent, child);
+            }
+        }
+        map.get_mapped(entity)
+    }
+
+    fn clone_entities(&mut self, sources: &[Entity]) -> EntityMap {
+        let mut map = EntityMap::default();
+        for source in sources.iter().copied() {
+            let target = self.spawn(());
+            map.insert(source, target);
+        }
+        for source in sources.iter().copied() {
+            let Some(signature) = self.get_entity_signature(source) else { continue; };
+            let target = map.get(source);
+            for index in signature.iter_ones() {
+                let component_id = ComponentId::new(index);
+                let Some(clone_fn) = self.components.clone_fn(component_id) else { continue; };
+                (clone_fn)(self, component_id, source, target, &map);
+            }
+        }
+        map
+    }
+
+    /// Entities with the given [`Name`], in the order they were named
+    #[inline]
+    pub fn entities_named(&self, name: &str) -> &[Entity] {
+        self.components.index::<Name>().map_or(&[], |names| names.get(name))
+    }
+
+    /// First entity given the name
+    #[inline]
+    pub fn entity_by_name(&self, name: &str) -> Option<Entity> {
+        self.entities_named(name).first().copied()
+    }
+
+    /// Entity whose `Debug` output includes its [`Name`]
+    #[inline]
+    pub fn named(&self, entity: Entity) -> NamedEntity<'_> {
+        NamedEntity::new(entity, self.get_component::<Name>(entity))
+    }
+
+    /// Hides the entity from queries, keeping its components and hierarchy
+    pub fn disable(&mut self, entity: Entity) {
+        if !self.is_alive(entity) || self.is_disabled(entity) { return; }
+        self.set_component(entity, Disabled);
+        self.trigger_lifecycle(OnDisable, entity);
+    }
+
+    pub fn enable(&mut self, entity: Entity) {
+        if !self.is_disabled(entity) { return; }
+        self.remove_component::<Disabled>(entity);
+        self.trigger_lifecycle(OnEnable, entity);
+    }
+
+    #[inline]
+    pub fn is_disabled(&self, entity: Entity) -> bool {
+        self.get_component::<Disabled>(entity).is_some()
+    }
+
+    #[inline]
+    pub fn add_child(&mut self, parent: Entity, child: Entity) {
+        self.set_parent(child, parent);
+    }
+
+    /// Moves child under parent, rejected operations are passed to the error handler
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) {
+        if !self.is_alive(parent) || !self.is_alive(child) { return; }
+        if let Err(err) = self.entities.set_parent(child, parent) {
+            let err = err.with_names(|entity| self.get_component::<Name>(entity).cloned());
+            self.handle_error(err.into());
+        }
+    }
+
+    /// Moves child under parent at the given position among its siblings, rejected operations are passed to the error handler
+    pub fn insert_child_at(&mut self, parent: Entity, index: usize, child: Entity) {
+        if !self.is_alive(parent) || !self.is_alive(child) { return; }
+        if let Err(err) = self.entities.insert_child_at(parent, index, child) {
+            let err = err.with_names(|entity| self.get_component::<Name>(entity).cloned());
+            self.handle_error(err.into());
+        }
+    }
+
+    /// Moves an existing child to the given position among its siblings
+    pub fn move_child(&mut self, parent: Entity, child: Entity, index: usize) {
+        if !self.is_alive(parent) { return; }
+        self.entities.move_child(parent, child, index);
+    }
+
+    /// Stable sorts parent's children by the key extracted with f
+    pub fn sort_children_by_key<K: Ord, F: FnMut(&World, Entity) -> K>(&mut self, parent: Entity, mut f: F) {
+        if !self.is_alive(parent) { return; }
+        let mut keyed: Vec<(K, Entity)> = self.entities.children(parent).iter()
+            .map(|child| (f(self, *child), *child))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.entities.reorder_children(parent, keyed.into_iter().map(|(_, child)| child).collect());
+    }
+
+    pub fn remove_parent(&mut self, child: Entity) {
+        if !self.is_alive(child) { return; }
+        self.entities.remove_parent(child);
+    }
+
+    pub fn remove_child(&mut self, parent: Entity, child: Entity) {
+        if !self.is_alive(parent) { return; }
+        self.entities.remove_child(parent, child);
+    }
+
+    pub fn remove_children(&mut self, entity: Entity) {
+        if !self.is_alive(entity) { return; }
+        self.entities.remove_children(entity);
+    }
+
+    /// Children in their sibling order
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        if !self.is_alive(entity) { return &[]; }
+        self.entities.children(entity)
+    }
+
+    #[inline]
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        if !self.is_alive(entity) { return None; }
+        self.entities.parent(entity)
+    }
+
+    #[inline]
+    pub fn ancestors(&self, entity: Entity) -> entity::Ancestors<'_> {
+        entity::Ancestors::new(&self.entities, self.is_alive(entity).then_some(entity))
+    }
+
+    /// Depth-first traversal of the entity's subtree
+    #[inline]
+    pub fn descendants(&self, entity: Entity) -> entity::DescendantsDepthFirst<'_> {
+        entity::DescendantsDepthFirst::new(&self.entities, self.is_alive(entity).then_some(entity))
+    }
+
+    #[inline]
+    pub fn descendants_breadth_first(&self, entity: Entity) -> entity::DescendantsBreadthFirst<'_> {
+        entity::DescendantsBreadthFirst::new(&self.entities, self.is_alive(entity).then_some(entity))
+    }
+
+    // ===== Relations =====
+
+
+    /// Relates source to target through R, reads as "source R target"
+    pub fn relate<R: Relationship>(&mut self, source: Entity, target: Entity) {
+        if !self.is_alive(source) || !self.is_alive(target) { return; }
+        self.relations.relate::<R>(source, target);
+    }
+
+    pub fn unrelate<R: Relationship>(&mut self, source: Entity, target: Entity) {
+        if !self.is_alive(source) || !self.is_alive(target) { return; }
+        self.relations.unrelate::<R>(source, target);
+    }
+
+    /// Entities that source is related to through R
+    pub fn targets<R: Relationship>(&self, source: Entity) -> &[Entity] {
+        if !self.is_alive(source) { return &[]; }
+        self.relations.targets::<R>(source)
+    }
+
+    /// Entities that are related to target through R
+    pub fn sources<R: Relationship>(&self, target: Entity) -> &[Entity] {
+        if !self.is_alive(target) { return &[]; }
+        self.relations.sources::<R>(target)
+    }
+
+
+    // ===== Triggers =====
+    
+
+    pub(crate) fn trigger_from_system<E: Send + Sync + 'static>(&mut self, event: E, target: Option<Entity>) -> Result<(), InternalSystemError> {
+        let mut world_ptr = self.world_ptr_mut();
+        unsafe { world_ptr.as_world_mut() }.observers.trigger(event, target, world_ptr)
+    }
+
+    pub fn trigger<E: Send + Sync + 'static>(&mut self, event: E, target: Option<Entity>) -> Result<(), InternalSystemError> {
+        self.remove_dead_observers();
+        let mut world_ptr = self.world_ptr_mut();
+        unsafe { world_ptr.as_world_mut() }.observers.trigger(event, target, world_ptr)
+    }
+
+    // triggers an event raised by the world itself, errors are reported instead of returned
+    #[inline]
+    pub(crate) fn trigger_lifecycle<E: Send + Sync + 'static>(&mut self, event: E, entity: Entity) {
+        if let Err(err) = self.trigger_from_system(event, Some(entity)) {
+            log::warn!("{}", err);
+            self.send_event(err);
+        }
+    }
+
+    #[inline]
+    fn trigger_inserted<C: Component>(&mut self, entity: Entity, component_id: ComponentId, added: bool) {
+        if added && self.components.is_observed(component_id, LifecycleEvent::Add, &self.observers) {
+            self.trigger_lifecycle(OnAdd::<C>::new(), entity);
+        }
+        if self.components.is_observed(component_id, LifecycleEvent::Insert, &self.observers) {
+            self.trigger_lifecycle(OnInsert::<C>::new(), entity);
+        }
+    }
+
+    /// Triggers for a component written by a bundle, once the whole bundle is in place
+    #[inline]
+    pub(crate) fn trigger_added<C: Component>(&mut self, entity: Entity) {
+        if self.observers.is_empty() { return; }
+        let Some(component_id) = self.components.get_component_id::<C>() else { return; };
+        self.trigger_inserted::<C>(entity, component_id, true);
+    }
+
+    // runs before the entity loses its components so observers can still read them
+    fn trigger_despawned(&mut self, entity: Entity) {
+        if self.observers.is_empty() { return; }
+        if self.observers.despawn_observed() {
+            self.trigger_lifecycle(OnDespawn, entity);
+        }
+        let Some(signature) = self.get_entity_signature(entity) else { return; };
+        self.trigger_removed(entity, &signature);
+    }
+
+    // runs the remove observers of the signature's components the entity has, false if they despawned it
+    fn trigger_removed(&mut self, entity: Entity, signature: &Signature) -> bool {
+        if self.observers.is_empty() { return true; }
+        for component_id in signature.iter_ones().map(ComponentId::new) {
+            if !self.is_alive(entity) { return false; }
+            if self.components.contains(entity, component_id) && self.components.is_observed(component_id, LifecycleEvent::Remove, &self.observers) {
+                (self.components.lifecycle_trigger(component_id, LifecycleEvent::Remove))(self, entity);
+            }
+        }
+        self.is_alive(entity)
+    }
+
+
+    // ===== Observers =====
+
+
+    #[inline]
+    pub fn add_observer<ParamIn: ObserverInput, Output: SystemOutput, S: IntoSystem<ParamIn, TriggerInput, Output> + 'static>(&mut self, system: S) -> SystemId {
+        self.observers.add_observer(system)
+    }
+
+    #[inline]
+    pub fn add_observer_with_id<ParamIn: ObserverInput, Output: SystemOutput, S: IntoSystem<ParamIn, TriggerInput, Output> + 'static>(&mut self, system: S, id: SystemId) {
+        let boxed_system = Box::new(system.into_system_with_id(id));
+        self.observers.add_boxed_observer(boxed_system);
+    }
+
+    #[inline]
+    pub(crate) fn remove_dead_observers(&mut self) {
+        self.observers.remove_dead_observers();
+    }
+
+
+    // ===== Systems =====
+
+
+    #[inline]
+    pub fn add_system<L: ScheduleLabel, ParamIn: SystemInput, Output: SystemOutput, S: IntoSystem<ParamIn, (), Output> + 'static>(&mut self, label: L, system: S) -> SystemId {
+        let schedule = self.schedules.get_or_default(label);
+        let boxed_system = Box::new(system.into_system());
+        let id = boxed_system.id().clone();
+        schedule.add_boxed_system(boxed_system);
+        id
+    }
+
+    #[inline]
+    pub(crate) fn add_system_with_id<L: ScheduleLabel, ParamIn: SystemInput, Output: SystemOutput, S: IntoSystem<ParamIn, (), Output> + 'static>(&mut self, label: L, system: S, id: SystemId) {
+        let schedule = self.schedules.get_or_default(label);
+        let boxed_system = Box::new(system.into_system_with_id(id));
+        schedule.add_boxed_system(boxed_system);
+    }
+
+
+    // ===== Events =====
+
+
+    #[inline]
+    pub fn send_event<E: Send + Sync + 'static>(&mut self, event: E) {
+        let event_queue = self.get_resource_ref_or_insert_with(|| EventQueue::<E>::new());
+        event_queue.send(event);
+    }
+
+    #[inline]
+    pub fn register_event<E: Send + Sync + 'static>(&mut self) {
+        self.get_resource_or_insert_with(|| EventQueue::<E>::new());
+    }
+
+
+    // ===== Error handling =====
+
+    #[inline]
+    pub fn set_error_handler<ParamIn: ErrorHandlerInput, Output: SystemOutput, S: IntoSystem<ParamIn, ECSError, Output> + 'static>(&mut self, error_handler: S) {
+        let mut boxed_system = Box::new(error_handler.into_system());
+        if let Err(err) = boxed_system.init(self) {
+            self.handle_error(ECSError::from(err));
+            return;
+        }
+        self.error_handler = boxed_system;
+    }
+
+    #[inline]
+    pub(crate) fn handle_error(&mut self, error: ECSError) {
+        let mut world_ptr = self.world_ptr_mut();
+        unsafe { world_ptr.as_world_mut() }.error_handler.execute(world_ptr, error);
+    }
+
+    
+    // ===== Other =====
+    
+
+    #[inline]
+    pub fn query<D: QueryData>(&mut self) -> Query<'_, D, ()> {
+        Query::new(self).expect("incorrect query parameters")
+    }
+
+    #[inline]
+    pub fn query_filtered<D: QueryData, F: QueryFilter>(&mut self) -> Query<'_, D, F> {
+        Query::new(self).expect("incorrect query parameters")
+    }
+
+    /// Query over components registered at runtime, see [`World::register_dynamic_component`].
+    /// Not available to systems, see [`DynamicQuery`].
+    #[inline]
+    pub fn query_dynamic(&mut self, component_ids: &[ComponentId]) -> DynamicQuery<'_> {
+        DynamicQuery::new(self, component_ids)
+    }
+
+    #[inline]
+    pub(crate) const fn command_buffer(&mut self) -> Commands<'_> {
+        Commands::new(&mut self.command_buffer, &self.entities)
+    }
+
+    #[inline]
+    pub(crate) fn process_command_buffer(&mut self) {
+        let mut queue = Vec::new();
+        std::mem::swap(&mut queue, &mut self.command_buffer);
+        Commands::process(&mut queue, self);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct WorldPtr<'a> {
+    ptr: NonNull<World>,
+    allow_mutable_access: bool,
+    _m: PhantomData<&'a mut World>,
+}
+
+unsafe impl Sync for WorldPtr<'_> {}
+unsafe impl Send for WorldPtr<'_> {}
+
+impl<'a> WorldPtr<'a> {
+    #[inline]
+    pub const unsafe fn as_world(&self) -> &'a World {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    #[inline]
+    pub const unsafe fn as_world_mut(&mut self) -> &'a mut World {
+        debug_assert!(self.allow_mutable_access);
+        unsafe { self.ptr.as_mut() }
+    }
+
+    #[inline]
+    pub const fn demote(&mut self) {
+        self.allow_mutable_access = false;
+    }
+}
+
+pub struct WorldResMut<'a, R: Resource> {
+    world_ptr: WorldPtr<'a>,
+    val: &'a mut R,
+    was_modified: bool,
+}
+
+impl<R: Resource> Drop for WorldResMut<'_, R> {
+    fn drop(&mut self) {
+        let world = unsafe { self.world_ptr.as_world_mut() };
+        if self.was_modified {
+            world.send_event(Changed::<R>::new());
+            if let Err(err) = world.trigger(Changed::<R>::new(), None) {
+                log::warn!("{}", err);
+                world.send_event(err);
+            }
+        }
+    }
+}
+
+impl<R: Resource> Deref for WorldResMut<'_, R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        self.val
+    }
+}
+
+impl<R: Resource> DerefMut for WorldResMut<'_, R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.was_modified = true;
+        self.val
+    }
+}
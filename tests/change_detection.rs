@@ -24,3 +24,63 @@ fn integration() {
     schedule.run(&mut world);
     assert!(X.load(Ordering::Relaxed) == 2, "{}", X.load(Ordering::Relaxed));
 }
+
+#[test]
+fn components() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    static ADDED: AtomicU32 = AtomicU32::new(0);
+    static CHANGED: AtomicU32 = AtomicU32::new(0);
+    static REF_CHANGED: AtomicU32 = AtomicU32::new(0);
+    #[derive(Component)]
+    struct Position(u32);
+    let mut world = World::default();
+    let a = world.spawn(Position(0));
+    let b = world.spawn(Position(0));
+
+    let mut detect = Schedule::default();
+    detect.add_system(|added: Query<&Position, Added<Position>>, changed: Query<&Position, Changed<Position>>, positions: Query<Ref<Position>>| {
+        ADDED.store(added.iter().count() as u32, Ordering::Relaxed);
+        CHANGED.store(changed.iter().count() as u32, Ordering::Relaxed);
+        REF_CHANGED.store(positions.iter().filter(|position| position.is_changed()).count() as u32, Ordering::Relaxed);
+    });
+    let mut write = Schedule::default();
+    write.add_system(move |mut positions: Query<(Entity, Mut<Position>)>| {
+        for (entity, mut position) in positions.iter_mut() {
+            // only a mutable dereference marks the component changed
+            if entity == a {
+                position.0 += 1;
+            } else {
+                assert_eq!(position.0, 0);
+            }
+        }
+    });
+
+    detect.run(&mut world);
+    assert_eq!(ADDED.load(Ordering::Relaxed), 2);
+    assert_eq!(CHANGED.load(Ordering::Relaxed), 2);
+
+    detect.run(&mut world);
+    assert_eq!(ADDED.load(Ordering::Relaxed), 0);
+    assert_eq!(CHANGED.load(Ordering::Relaxed), 0);
+
+    write.run(&mut world);
+    detect.run(&mut world);
+    assert_eq!(ADDED.load(Ordering::Relaxed), 0);
+    assert_eq!(CHANGED.load(Ordering::Relaxed), 1);
+    assert_eq!(REF_CHANGED.load(Ordering::Relaxed), 1);
+
+    world.get_component_mut::<Position>(b).unwrap().0 = 5;
+    world.spawn(Position(0));
+    detect.run(&mut world);
+    assert_eq!(ADDED.load(Ordering::Relaxed), 1);
+    assert_eq!(CHANGED.load(Ordering::Relaxed), 2);
+    assert_eq!(world.get_component_ticks::<Position>(b).map(|ticks| ticks.changed != ticks.added), Some(true));
+
+    // tables created after the systems first ran are matched too
+    #[derive(Component)]
+    struct Marker;
+    world.spawn((Position(0), Marker));
+    detect.run(&mut world);
+    assert_eq!(ADDED.load(Ordering::Relaxed), 1);
+    assert_eq!(CHANGED.load(Ordering::Relaxed), 1);
+}
@@ -76,3 +76,57 @@ fn layered() {
     assert!(world.get_resource::<SuccessI>().is_some());
     assert!(world.get_resource::<SuccessJ>().is_some());
 }
+
+#[test]
+fn entity_commands() {
+    #[derive(Component, Debug, PartialEq)] struct Health(u32);
+    #[derive(Component, Debug, PartialEq)] struct Armor(u32);
+    #[derive(Component)] struct Tag;
+    struct Hit;
+    #[derive(Resource, Default)] struct Hits(Vec<Entity>);
+
+    let mut world = World::default();
+    world.insert_resource(Hits::default());
+    world.add_observer(|trigger: Trigger<Hit>, mut hits: ResMut<Hits>| hits.0.push(trigger.target().unwrap()));
+    let existing = world.spawn((Health(1), Tag));
+    let orphan = world.spawn(());
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands| {
+        let mut children = Vec::new();
+        let parent = commands.spawn(Health(10))
+            .insert(Armor(5))
+            .insert_if_new((Health(0), Tag))
+            .add_child(orphan)
+            .with_children(|parent| {
+                children.push(parent.spawn(Health(2)).insert(Armor(1)).id());
+                children.push(parent.spawn(()).id());
+            })
+            .trigger(Hit)
+            .id();
+        commands.entity(existing)
+            .insert_if_new(Armor(3))
+            .insert(Health(7))
+            .remove::<Tag>();
+        tx.send((parent, children)).unwrap();
+    });
+    schedule.run(&mut world);
+    let (parent, children) = rx.recv().unwrap();
+
+    assert_eq!(world.get_component::<Health>(parent), Some(&Health(10)));
+    assert_eq!(world.get_component::<Armor>(parent), Some(&Armor(5)));
+    assert!(world.get_component::<Tag>(parent).is_some());
+    assert_eq!(world.children(parent), &[orphan, children[0], children[1]]);
+    assert_eq!(world.get_component::<Armor>(children[0]), Some(&Armor(1)));
+    assert_eq!(world.get_resource::<Hits>().unwrap().0, vec![parent]);
+
+    assert_eq!(world.get_component::<Health>(existing), Some(&Health(7)));
+    assert_eq!(world.get_component::<Armor>(existing), Some(&Armor(3)));
+    assert!(world.get_component::<Tag>(existing).is_none());
+
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands| commands.entity(parent).despawn());
+    schedule.run(&mut world);
+    assert!(!world.is_alive(parent) && !world.is_alive(children[0]) && !world.is_alive(orphan));
+}
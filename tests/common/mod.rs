@@ -44,3 +44,20 @@ impl ComponentB {
 pub fn panic_unit() {
     panic!();
 }
+
+/// A distinct type for every pair of indices, for tests that need more components or resources than fit in 128 bits
+#[derive(Default)]
+pub struct Marker<const A: usize, const B: usize>(pub std::sync::atomic::AtomicBool);
+impl<const A: usize, const B: usize> Component for Marker<A, B> {}
+impl<const A: usize, const B: usize> Resource for Marker<A, B> {}
+
+/// Expands `$callback!(a, b)` for every a in the first list and b in the second
+#[macro_export]
+macro_rules! for_each_marker {
+    ($callback:ident; $($a:literal)*; $b:tt) => {
+        $(for_each_marker!(@row $callback, $a; $b);)*
+    };
+    (@row $callback:ident, $a:literal; [$($b:literal)*]) => {
+        $($callback!($a, $b);)*
+    };
+}
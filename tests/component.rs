@@ -48,6 +48,16 @@ fn set() {
     });
 }
 
+#[test]
+fn remove() {
+    let mut world = World::default();
+    let entity = world.spawn((ComponentA::new(0), ComponentB(String::from("0"))));
+    world.remove_component::<ComponentB>(entity);
+    assert!(world.get_component::<ComponentB>(entity).is_none());
+    assert_eq!(world.query::<&ComponentB>().iter().count(), 0);
+    assert_eq!(world.query::<&ComponentA>().iter().count(), 1);
+}
+
 #[test]
 fn component_hooks() {
     struct Spawner;
@@ -92,3 +102,407 @@ fn component_hooks() {
     world.remove_resource::<Spawner>();
     assert!(world.query::<&Rotation>().iter().count() == 4);
 }
+
+#[test]
+fn many_components() {
+    let mut world = World::default();
+    let entity = world.spawn(());
+    let other = world.spawn(Marker::<0, 0>::default());
+    macro_rules! insert_marker {
+        ($a:literal, $b:literal) => { world.set_component(entity, Marker::<$a, $b>::default()); };
+    }
+    for_each_marker!(insert_marker; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15; [0 1 2 3 4 5 6 7 8 9]);
+    assert_eq!(world.get_entity_signature(entity).unwrap().ones(), 160);
+
+    assert_eq!(world.query::<&Marker<0, 0>>().iter().count(), 2);
+    assert_eq!(world.query::<&Marker<15, 9>>().iter().count(), 1);
+    assert_eq!(world.query_filtered::<Entity, Without<Marker<15, 9>>>().iter().collect::<Vec<_>>(), vec![other]);
+
+    world.remove_component::<Marker<15, 9>>(entity);
+    assert_eq!(world.query::<&Marker<15, 9>>().iter().count(), 0);
+    assert_eq!(world.query::<(&Marker<0, 0>, &Marker<15, 8>)>().iter().count(), 1);
+    assert_eq!(world.get_entity_signature(entity).unwrap().ones(), 159);
+
+    world.despawn(entity);
+    assert_eq!(world.query::<&Marker<15, 8>>().iter().count(), 0);
+    assert_eq!(world.query::<&Marker<0, 0>>().iter().count(), 1);
+}
+
+#[test]
+fn component_hooks_context() {
+    #[derive(Component)]
+    struct Log(&'static str, Entity, ComponentId);
+    #[derive(Component)]
+    struct Tracked;
+    #[derive(Component)]
+    struct Child;
+    struct Parent;
+    impl Component for Parent {
+        fn register_component_hooks(hooks: &mut ComponentHooks) {
+            hooks.on_add(|commands, context| {
+                commands.entity(context.entity).with_children(|spawner| { spawner.spawn(Child); });
+            });
+        }
+    }
+
+    let mut world = World::default();
+    world.register_component_hooks::<Tracked>()
+        .on_add(|commands, context| { commands.spawn(Log("add", context.entity, context.component_id)); })
+        .on_insert(|commands, context| { commands.spawn(Log("insert", context.entity, context.component_id)); })
+        .on_replace(|commands, context| { commands.spawn(Log("replace", context.entity, context.component_id)); })
+        .on_remove(|commands, context| { commands.spawn(Log("remove", context.entity, context.component_id)); })
+        .on_despawn(|commands, context| { commands.spawn(Log("despawn", context.entity, context.component_id)); });
+    let tracked_id = world.register_component::<Tracked>();
+
+    let entity = world.spawn(Tracked);
+    world.set_component(entity, Tracked);
+    world.remove_component::<Tracked>(entity);
+    world.remove_component::<Tracked>(entity);
+    world.set_component(entity, Tracked);
+    world.despawn(entity);
+
+    let logs: Vec<&'static str> = world.query::<&Log>().iter()
+        .inspect(|log| assert_eq!((log.1, log.2), (entity, tracked_id)))
+        .map(|log| log.0)
+        .collect();
+    assert_eq!(logs, [
+        "add", "insert",
+        "replace", "insert",
+        "replace", "remove",
+        "add", "insert",
+        "despawn", "replace", "remove",
+    ]);
+
+    let parent = world.spawn(Parent);
+    let children = world.children(parent);
+    assert_eq!(children.len(), 1, "on_add hook didn't attach a child");
+    assert!(world.get_component::<Child>(children[0]).is_some());
+}
+
+#[test]
+fn required_components() {
+    #[derive(Component, Default, PartialEq, Debug)]
+    struct Transform(u32);
+    #[derive(Component, Default, PartialEq, Debug)]
+    struct GlobalTransform(u32);
+    #[derive(Component, Default, PartialEq, Debug)]
+    #[require(GlobalTransform)]
+    struct Visibility(bool);
+    #[derive(Component)]
+    #[require(Transform, Visibility)]
+    struct Sprite;
+    let mut world = World::default();
+
+    let a = world.spawn(Sprite);
+    assert_eq!(world.get_component::<Transform>(a), Some(&Transform(0)));
+    assert_eq!(world.get_component::<Visibility>(a), Some(&Visibility(false)));
+    assert_eq!(world.get_component::<GlobalTransform>(a), Some(&GlobalTransform(0)));
+    assert_eq!(world.query::<(&Sprite, &Transform, &Visibility, &GlobalTransform)>().iter().count(), 1);
+
+    // components given explicitly win over the defaults
+    let b = world.spawn((Sprite, Transform(5)));
+    assert_eq!(world.get_component::<Transform>(b), Some(&Transform(5)));
+    assert_eq!(world.get_component::<GlobalTransform>(b), Some(&GlobalTransform(0)));
+
+    let c = world.spawn(GlobalTransform(3));
+    world.set_component(c, Sprite);
+    assert_eq!(world.get_component::<Transform>(c), Some(&Transform(0)));
+    assert_eq!(world.get_component::<GlobalTransform>(c), Some(&GlobalTransform(3)));
+
+    let batch = world.spawn_batch([Sprite, Sprite]);
+    assert!(batch.iter().all(|entity| world.get_component::<Visibility>(*entity).is_some()));
+
+    let mut schedule = Schedule::default();
+    schedule.add_system(|mut commands: Commands| {
+        commands.spawn(Sprite);
+    });
+    schedule.run(&mut world);
+    assert_eq!(world.query::<(&Sprite, &Transform, &Visibility, &GlobalTransform)>().iter().count(), 6);
+}
+
+#[test]
+#[should_panic]
+fn required_components_cycle_panic() {
+    #[derive(Component, Default)]
+    #[require(B)]
+    struct A;
+    #[derive(Component, Default)]
+    #[require(C)]
+    struct B;
+    #[derive(Component, Default)]
+    #[require(A)]
+    struct C;
+    let mut world = World::default();
+    world.register_component::<B>();
+}
+
+#[test]
+fn bundles() {
+    #[derive(Component, PartialEq, Debug)]
+    struct Position(u32);
+    #[derive(Component, PartialEq, Debug)]
+    struct Velocity(u32);
+    #[derive(Component, PartialEq, Debug)]
+    struct Health(u32);
+    #[derive(Bundle, PartialEq, Debug)]
+    struct Movement {
+        position: Position,
+        velocity: Velocity,
+    }
+    #[derive(Bundle, PartialEq, Debug)]
+    struct Unit(Movement, Health);
+    let mut world = World::default();
+    world.add_observer(|trigger: Trigger<OnAdd<Health>>, query: Query<&Position>| {
+        // the whole bundle is written before any observer runs
+        assert!(query.get(trigger.target().unwrap()).is_some());
+    });
+
+    let unit = world.spawn(Unit(Movement { position: Position(1), velocity: Velocity(2) }, Health(3)));
+    assert_eq!(world.query::<(&Position, &Velocity, &Health)>().iter().count(), 1);
+
+    assert_eq!(world.take::<(Health, Name)>(unit), None);
+    assert_eq!(world.take::<Movement>(unit), Some(Movement { position: Position(1), velocity: Velocity(2) }));
+    assert_eq!(world.take::<Movement>(unit), None);
+    assert_eq!(world.get_component::<Health>(unit), Some(&Health(3)));
+
+    world.entity_mut(unit).insert(Movement { position: Position(4), velocity: Velocity(5) });
+    world.remove_bundle::<(Unit, Name)>(unit);
+    assert!(world.is_alive(unit));
+    assert_eq!(world.get_entity_signature(unit), Some(Signature::new()));
+
+    let batch = world.spawn_batch((0..3).map(|i| Movement { position: Position(i), velocity: Velocity(i) }));
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands| {
+        commands.entity(batch[0]).remove_bundle::<Movement>();
+        commands.entity(batch[1]).take::<Movement, _>(move |world, movement| { world.spawn((movement, Health(0))); });
+    });
+    schedule.run(&mut world);
+    assert_eq!(world.query::<&Position>().iter().count(), 2);
+    assert_eq!(world.query::<(&Position, &Health)>().iter().count(), 1);
+}
+
+#[test]
+fn dynamic_components() {
+    use std::{alloc::Layout, mem::ManuallyDrop, ptr::NonNull, sync::atomic::{AtomicUsize, Ordering}};
+    #[derive(Component)]
+    struct Position(u32);
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    unsafe fn drop_string(mut ptr: PtrMut<'_>) {
+        DROPS.fetch_add(1, Ordering::Relaxed);
+        unsafe { ptr.as_ptr().cast::<String>().drop_in_place() };
+    }
+    unsafe fn clone_string(source: Ptr<'_>, mut target: PtrMut<'_>) {
+        unsafe { target.as_ptr().cast::<String>().write(source.cast_ref::<String>().clone()) };
+    }
+    let mut world = World::default();
+    let counter = world.register_dynamic_component(ComponentDescriptor::new("counter", Layout::new::<u64>()));
+    let label = world.register_dynamic_component(
+        ComponentDescriptor::new("label", Layout::new::<String>()).with_drop(drop_string).with_clone(clone_string)
+    );
+    assert_ne!(counter, label);
+    assert_eq!(world.component_name(label), Some("label"));
+
+    let entity = world.spawn(Position(0));
+    let mut value = 7u64;
+    unsafe { world.insert_by_id(entity, counter, PtrMut::new(NonNull::from(&mut value).cast())) };
+    let mut value = ManuallyDrop::new(String::from("first"));
+    unsafe { world.insert_by_id(entity, label, PtrMut::new(NonNull::from(&mut value).cast())) };
+    let mut value = ManuallyDrop::new(String::from("second"));
+    unsafe { world.insert_by_id(entity, label, PtrMut::new(NonNull::from(&mut value).cast())) };
+    assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+
+    assert_eq!(unsafe { world.get_by_id(entity, counter).unwrap().cast_ref::<u64>() }, &7);
+    assert_eq!(unsafe { world.get_by_id(entity, label).unwrap().cast_ref::<String>() }, "second");
+    *unsafe { world.get_by_id_mut(entity, counter).unwrap().cast_mut::<u64>() } += 1;
+
+    let copy = world.clone_entity(entity).unwrap();
+    assert_eq!(unsafe { world.get_by_id(copy, label).unwrap().cast_ref::<String>() }, "second");
+    assert!(world.get_by_id(copy, counter).is_none());
+
+    let mut query = world.query_dynamic(&[label]);
+    assert_eq!(query.iter().count(), 2);
+    for (_, mut components) in query.iter_mut() {
+        unsafe { components[0].cast_mut::<String>().push('!') };
+    }
+    let position = world.register_component::<Position>();
+    let mut query = world.query_dynamic(&[counter, label]).with(&[position]);
+    let items = query.iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].0, entity);
+    assert_eq!(unsafe { items[0].1[0].cast_ref::<u64>() }, &8);
+    assert_eq!(unsafe { items[0].1[1].cast_ref::<String>() }, "second!");
+    assert!(query.get_mut(copy).is_none());
+    assert_eq!(world.query_dynamic(&[label]).without(&[counter]).iter().count(), 1);
+
+    world.remove_by_id(entity, label);
+    assert!(world.get_by_id(entity, label).is_none());
+    assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+    world.despawn(copy);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn immutable_components() {
+    #[derive(Component, PartialEq, Debug)]
+    #[component(immutable)]
+    struct Team(u32);
+    #[derive(Component)]
+    struct Replaced;
+    let mut world = World::default();
+    world.register_component_hooks::<Team>()
+        .on_replace(|commands, _| { commands.spawn(Replaced); });
+
+    let entity = world.spawn(Team(1));
+    world.set_component(entity, Team(2));
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands, query: Query<(Entity, Ref<Team>)>| {
+        for (entity, team) in query.iter() {
+            commands.entity(entity).insert(Team(team.0 + 1));
+        }
+    });
+    schedule.run(&mut world);
+    assert_eq!(world.get_component::<Team>(entity), Some(&Team(3)));
+    assert_eq!(world.query::<&Replaced>().iter().count(), 2);
+}
+
+#[test]
+#[should_panic]
+fn immutable_components_by_id_panic() {
+    #[derive(Component)]
+    #[component(immutable)]
+    struct Team;
+    let mut world = World::default();
+    let entity = world.spawn(Team);
+    let team = world.register_component::<Team>();
+    world.get_by_id_mut(entity, team);
+}
+
+#[test]
+fn indexes() {
+    #[derive(Component, Clone, PartialEq, Eq, Hash, Debug)]
+    #[component(immutable)]
+    struct Team(u32);
+    #[derive(Component, Clone, PartialEq, Eq, Hash, Debug)]
+    #[component(immutable)]
+    struct GridCell(i32, i32);
+    let mut world = World::default();
+    let a = world.spawn(Team(1));
+    let b = world.spawn((Team(2), GridCell(3, 4)));
+    world.register_component_index::<Team>();
+    assert_eq!(world.index::<Team>().get(&Team(1)), [a]);
+
+    let c = world.spawn(Team(2));
+    world.set_component(a, Team(2));
+    assert_eq!(world.index::<Team>().get(&Team(2)), [b, c, a]);
+    assert!(!world.index::<Team>().contains(&Team(1)));
+
+    world.remove_component::<Team>(c);
+    world.despawn(b);
+    assert_eq!(world.index::<Team>().get(&Team(2)), [a]);
+    assert_eq!(world.take::<Team>(a), Some(Team(2)));
+    assert!(world.index::<Team>().is_empty());
+
+    let d = world.spawn(GridCell(3, 4));
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands, cells: Index<GridCell>| {
+        assert_eq!(cells.get(&GridCell(3, 4)), [d]);
+        commands.entity(d).insert(GridCell(5, 6));
+    });
+    schedule.run(&mut world);
+    assert_eq!(world.index::<GridCell>().first(&GridCell(5, 6)), Some(d));
+    assert!(!world.index::<GridCell>().contains(&GridCell(3, 4)));
+}
+
+#[test]
+fn mutable_indexes() {
+    #[derive(Component, Clone, PartialEq, Eq, Hash, Debug)]
+    struct Score(u32);
+    let mut world = World::default();
+    world.register_component_index::<Score>();
+    let [a, b, c] = [0, 1, 2].map(|_| world.spawn(Score(1)));
+
+    world.get_component_mut::<Score>(a).unwrap().0 = 2;
+    assert_eq!(world.index::<Score>().get(&Score(1)), [b, c]);
+    assert_eq!(world.index::<Score>().get(&Score(2)), [a]);
+
+    // changed in place and despawned before the index noticed
+    world.get_component_mut::<Score>(c).unwrap().0 = 3;
+    world.despawn(c);
+    assert_eq!(world.index::<Score>().get(&Score(1)), [b]);
+    assert!(!world.index::<Score>().contains(&Score(3)));
+
+    let mut update = Schedule::default();
+    update.add_system(|mut query: Query<&mut Score>| {
+        for mut score in query.iter_mut() {
+            score.0 += 10;
+        }
+    });
+    let mut lookup = Schedule::default();
+    lookup.add_system(move |scores: Index<Score>| {
+        assert_eq!(scores.get(&Score(12)), [a]);
+        assert_eq!(scores.get(&Score(11)), [b]);
+        assert_eq!(scores.len(), 2);
+    });
+    update.run(&mut world);
+    lookup.run(&mut world);
+}
+
+#[test]
+fn storage_types() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    #[derive(Component, PartialEq, Debug)]
+    struct Position(i32);
+    #[derive(Component, PartialEq, Debug)]
+    #[component(storage = "SparseSet")]
+    struct Marker(String);
+    #[derive(Component)]
+    struct Counted;
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    assert_eq!(Position::STORAGE_TYPE, StorageType::Table);
+    assert_eq!(Marker::STORAGE_TYPE, StorageType::SparseSet);
+
+    let mut world = World::default();
+    let entities: Vec<Entity> = (0..10).map(|i| world.spawn((Position(i), Counted))).collect();
+    for entity in entities.iter().step_by(2) {
+        world.set_component(*entity, Marker(format!("{}", entity.id())));
+    }
+    world.set_component(entities[1], Position(-1));
+    world.remove_component::<Counted>(entities[3]);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+    world.despawn(entities[4]);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+
+    assert_eq!(world.get_component::<Position>(entities[1]), Some(&Position(-1)));
+    assert_eq!(world.get_component::<Position>(entities[3]), Some(&Position(3)));
+    assert_eq!(world.get_component::<Marker>(entities[6]), Some(&Marker(format!("{}", entities[6].id()))));
+    assert_eq!(world.query::<(&Position, &Marker)>().iter().count(), 4);
+    assert_eq!(world.query::<&Counted>().iter().count(), 8);
+    for mut position in world.query::<&mut Position>().iter_mut() {
+        position.0 *= 2;
+    }
+    assert_eq!(world.get_component::<Position>(entities[9]), Some(&Position(18)));
+    assert_eq!(world.query::<Option<&Counted>>().iter().filter(Option::is_some).count(), 8);
+    assert_eq!(world.query_filtered::<&Position, Without<Marker>>().iter().count(), 5);
+    for mut marker in world.query::<&mut Marker>().iter_mut() {
+        marker.0.push('!');
+    }
+    assert_eq!(world.get_component::<Marker>(entities[2]), Some(&Marker(format!("{}!", entities[2].id()))));
+
+    let (position, marker) = world.take::<(Position, Marker)>(entities[8]).unwrap();
+    assert_eq!((position, marker), (Position(16), Marker(format!("{}!", entities[8].id()))));
+    assert!(world.get_component::<Position>(entities[8]).is_none());
+    assert!(world.get_component::<Counted>(entities[8]).is_some());
+    world.despawn(entities[8]);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 3);
+    assert_eq!(world.get_component::<Position>(entities[9]), Some(&Position(18)));
+
+    world.remove_bundle::<(Counted, Marker)>(entities[6]);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 4);
+    assert!(world.get_component::<Marker>(entities[6]).is_none());
+    assert_eq!(world.get_component::<Position>(entities[6]), Some(&Position(12)));
+}
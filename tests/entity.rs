@@ -89,3 +89,221 @@ fn integration() {
     assert!(!world.is_alive(old_f));
     assert!(!world.is_alive(old_d));
 }
+
+#[test]
+fn disable() {
+    #[derive(Component)] struct Bullet(u32);
+    #[derive(Resource, Default)] struct Toggles(Vec<(Entity, bool)>);
+
+    let mut world = World::default();
+    world.insert_resource(Toggles::default());
+    world.add_observer(|trigger: Trigger<OnDisable>, mut toggles: ResMut<Toggles>| toggles.0.push((trigger.target().unwrap(), false)));
+    world.add_observer(|trigger: Trigger<OnEnable>, mut toggles: ResMut<Toggles>| toggles.0.push((trigger.target().unwrap(), true)));
+
+    let a = world.spawn(Bullet(0));
+    let b = world.spawn(Bullet(1));
+    let child = world.spawn(Bullet(2));
+    world.add_child(b, child);
+
+    world.disable(b);
+    world.disable(b);
+    assert!(world.is_disabled(b));
+    assert_eq!(world.query::<Entity>().iter().collect::<Vec<_>>().len(), 2);
+    assert!(world.query::<&Bullet>().get(b).is_none());
+    assert!(world.query::<&Bullet>().get(a).is_some());
+    assert_eq!(world.query_filtered::<&Bullet, IncludeDisabled>().iter().count(), 3);
+    assert_eq!(world.query_filtered::<&Bullet, IncludeDisabled>().get(b).map(|bullet| bullet.0), Some(1));
+    assert_eq!(world.query_filtered::<Entity, With<Disabled>>().iter().collect::<Vec<_>>(), vec![b]);
+    assert_eq!(world.get_component::<Bullet>(b).map(|bullet| bullet.0), Some(1));
+    assert_eq!(world.children(b), &[child]);
+
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |query: Query<&Bullet>, mut commands: Commands| {
+        assert_eq!(query.iter().count(), 2);
+        commands.enable(b);
+        commands.disable(a);
+    });
+    schedule.run(&mut world);
+    assert!(!world.is_disabled(b) && world.is_disabled(a));
+    assert_eq!(world.get_resource::<Toggles>().unwrap().0, vec![(b, false), (b, true), (a, false)]);
+
+    world.despawn(a);
+    world.enable(a);
+    assert_eq!(world.get_resource::<Toggles>().unwrap().0.len(), 3);
+}
+
+#[test]
+fn clone_entity() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static ADDED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Health(u32);
+    impl Component for Health {
+        fn on_add(&mut self, _: &mut Commands) {
+            ADDED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Follow(Entity);
+    impl MapEntities for Follow {
+        fn map_entities(&mut self, map: &EntityMap) {
+            self.0 = map.get(self.0);
+        }
+    }
+
+    #[derive(Component)]
+    struct NotCloned;
+
+    let mut world = World::default();
+    world.register_component_clone::<Health>();
+    world.register_component_clone_mapped::<Follow>();
+
+    let outside = world.spawn(());
+    let root = world.spawn((Health(10), NotCloned));
+    let a = world.spawn((Health(5), Follow(root)));
+    let b = world.spawn(Follow(outside));
+    let a1 = world.spawn(Follow(a));
+    world.add_child(root, a);
+    world.add_child(root, b);
+    world.add_child(a, a1);
+    ADDED.store(0, Ordering::Relaxed);
+
+    let single = world.clone_entity(a).unwrap();
+    assert_eq!(world.get_component::<Health>(single), Some(&Health(5)));
+    assert_eq!(world.get_component::<Follow>(single), Some(&Follow(root)));
+    assert!(world.children(single).is_empty() && world.parent(single).is_none());
+    assert_eq!(ADDED.load(Ordering::Relaxed), 1);
+
+    let copy = world.clone_entity_recursive(root).unwrap();
+    assert_eq!(ADDED.load(Ordering::Relaxed), 3);
+    assert!(world.get_component::<NotCloned>(copy).is_none());
+    assert_eq!(world.get_component::<Health>(copy), Some(&Health(10)));
+    let &[copy_a, copy_b] = world.children(copy) else { panic!("children not cloned") };
+    let &[copy_a1] = world.children(copy_a) else { panic!("grandchildren not cloned") };
+    assert!(![root, a, b, a1].contains(&copy_a) && ![root, a, b, a1].contains(&copy_a1));
+    assert_eq!(world.get_component::<Follow>(copy_a), Some(&Follow(copy)));
+    assert_eq!(world.get_component::<Follow>(copy_b), Some(&Follow(outside)));
+    assert_eq!(world.get_component::<Follow>(copy_a1), Some(&Follow(copy_a)));
+    assert_eq!(world.children(root), &[a, b]);
+
+    world.despawn(root);
+    assert!(world.clone_entity(root).is_none());
+    assert!(world.is_alive(copy_a1));
+}
+
+#[test]
+fn spawn_batch() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static ADDED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Tracked;
+    impl Component for Tracked {
+        fn on_add(&mut self, _: &mut Commands) {
+            ADDED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let mut world = World::default();
+    let recycled = world.spawn_batch((0..4).map(|_| ()));
+    world.despawn(recycled[1]);
+    world.despawn(recycled[2]);
+
+    let entities = world.spawn_batch((0..1000).map(|i| (common::ComponentA::new(i % 256), Tracked)));
+    assert_eq!(entities.len(), 1000);
+    assert_eq!(ADDED.load(Ordering::Relaxed), 1000);
+    assert!(entities[..2].iter().all(|entity| entity.version() == 1));
+    let mut unique = entities.clone();
+    unique.sort_by_key(|entity| entity.id());
+    unique.dedup();
+    assert_eq!(unique.len(), 1000);
+    for (i, entity) in entities.iter().enumerate() {
+        assert!(world.get_component::<common::ComponentA>(*entity).unwrap().validate(i % 256));
+    }
+    assert_eq!(world.query::<(&common::ComponentA, &Tracked)>().iter().count(), 1000);
+
+    let mut schedule = Schedule::default();
+    let (tx, rx) = std::sync::mpsc::channel();
+    schedule.add_system(move |mut commands: Commands| {
+        tx.send(commands.spawn_batch((0..10).map(common::ComponentA::new))).unwrap();
+    });
+    schedule.run(&mut world);
+    let entities = rx.recv().unwrap();
+    for (i, entity) in entities.iter().enumerate() {
+        assert!(world.get_component::<common::ComponentA>(*entity).unwrap().validate(i));
+    }
+    assert_eq!(world.query::<&common::ComponentA>().iter().count(), 1010);
+}
+
+#[test]
+fn names() {
+    let mut world = World::default();
+    let player = world.spawn(Name::new("player"));
+    let [enemy_a, enemy_b] = [0, 1].map(|_| world.spawn(Name::from("enemy")));
+    let unnamed = world.spawn(());
+
+    assert_eq!(world.entity_by_name("player"), Some(player));
+    assert_eq!(world.entities_named("enemy"), &[enemy_a, enemy_b]);
+    assert!(world.entities_named("boss").is_empty());
+    assert_eq!(world.index::<Name>().get("player"), [player]);
+
+    world.set_component(enemy_a, Name::new("boss"));
+    assert_eq!(world.entities_named("enemy"), &[enemy_b]);
+    assert_eq!(world.entity_by_name("boss"), Some(enemy_a));
+    world.remove_component::<Name>(enemy_a);
+    assert!(world.entity_by_name("boss").is_none());
+    world.despawn(enemy_b);
+    assert!(world.entities_named("enemy").is_empty());
+
+    assert_eq!(format!("{:?}", world.named(player)), format!("{}v{} \"player\"", player.id(), player.version()));
+    assert_eq!(format!("{:?}", world.named(unnamed)), format!("{}v{}", unnamed.id(), unnamed.version()));
+    assert_eq!(format!("{:?}", world.named(enemy_b)), format!("{}v{}", enemy_b.id(), enemy_b.version()));
+
+    #[derive(Resource, Default)]
+    struct Errors(Vec<String>);
+    world.insert_resource(Errors::default());
+    world.set_error_handler(|err: error::ECSError, mut errors: ResMut<Errors>| {
+        assert!(err.downcast_ref::<HierarchyError>().is_some());
+        errors.0.push(err.to_string());
+    });
+    world.add_child(player, player);
+    let errors = &world.get_resource::<Errors>().unwrap().0;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0], format!("entity '{}v{} \"player\"' tried to be it's own parent", player.id(), player.version()));
+}
+
+#[test]
+fn entity_handles() {
+    #[derive(Component, Debug, PartialEq)] struct A(u32);
+    #[derive(Component, Debug, PartialEq)] struct B(u32);
+
+    let mut world = World::default();
+    let child = world.spawn(());
+    let entity = world.spawn(A(1));
+    world.entity_mut(entity)
+        .insert((A(2), B(3)))
+        .add_child(child);
+
+    let entity_ref = world.entity(entity);
+    assert_eq!(entity_ref.id(), entity);
+    assert_eq!(entity_ref.get::<A>(), Some(&A(2)));
+    assert!(entity_ref.contains::<B>());
+    assert_eq!(entity_ref.signature(), world.get_entity_signature(entity).unwrap());
+    assert_eq!(entity_ref.children(), &[child]);
+    assert_eq!(world.entity(child).parent(), Some(entity));
+
+    let mut entity_mut = world.entity_mut(entity);
+    entity_mut.get_mut::<B>().unwrap().0 = 4;
+    entity_mut.remove::<A>();
+    assert!(!entity_mut.contains::<A>());
+    assert_eq!(entity_mut.get::<B>(), Some(&B(4)));
+    assert_eq!(world.query::<&A>().iter().count(), 0);
+
+    world.entity_mut(entity).despawn();
+    assert!(!world.is_alive(entity) && !world.is_alive(child));
+    let err = world.get_entity(entity).err().unwrap();
+    assert_eq!(err.kind(), &EntityErrorKind::Dead(entity));
+    assert!(world.get_entity_mut(entity).is_err());
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| { world.entity(entity); })).is_err());
+}
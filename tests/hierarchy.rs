@@ -47,7 +47,7 @@ fn integration() {
 
     schedule.add_system(|mut commands: Commands, query: Query<Entity, With<Parent>>| {
         for entity in query.iter() {
-            let child = commands.spawn(Child);
+            let child = commands.spawn(Child).id();
             commands.add_child(entity, child);
         }
     });
@@ -91,3 +91,204 @@ fn integration() {
     world.despawn(parent);
     schedule.run(&mut world);
 }
+
+#[test]
+fn traversal() {
+    #[derive(Component)]
+    struct Node;
+
+    let mut world = World::default();
+    //        root
+    //       /    \
+    //      a      b
+    //     / \      \
+    //    c   d      e
+    let root = world.spawn(Node);
+    let a = world.spawn(Node);
+    let b = world.spawn(Node);
+    let c = world.spawn(Node);
+    let d = world.spawn(Node);
+    let e = world.spawn(Node);
+    world.add_child(root, a);
+    world.add_child(root, b);
+    world.add_child(a, c);
+    world.add_child(a, d);
+    world.add_child(b, e);
+
+    assert_eq!(world.parent(root), None);
+    assert_eq!(world.parent(c), Some(a));
+    assert_eq!(world.ancestors(c).collect::<Vec<_>>(), vec![a, root]);
+    assert_eq!(world.descendants(root).collect::<Vec<_>>(), vec![a, c, d, b, e]);
+    assert_eq!(world.descendants_breadth_first(root).collect::<Vec<_>>(), vec![a, b, c, d, e]);
+
+    let parents = world.query::<(Entity, Parent)>().iter().map(|(entity, parent)| (entity, parent.get())).collect::<Vec<_>>();
+    for (entity, parent) in parents {
+        assert_eq!(parent, world.parent(entity));
+    }
+
+    world.remove_child(a, d);
+    assert_eq!(world.parent(d), None);
+    assert_eq!(world.children(a), &[c]);
+
+    world.despawn(b);
+    assert_eq!(world.children(root), &[a]);
+    assert_eq!(world.parent(e), None);
+    assert!(!world.is_alive(e));
+    assert_eq!(world.descendants(root).collect::<Vec<_>>(), vec![a, c]);
+}
+
+#[test]
+fn reparent() {
+    #[derive(Component)]
+    struct Node;
+
+    #[derive(Resource, Default)]
+    struct Errors(Vec<HierarchyErrorKind>);
+
+    let mut world = World::default();
+    world.insert_resource(Errors::default());
+    world.set_error_handler(|err: error::ECSError, mut errors: ResMut<Errors>| {
+        errors.0.push(*err.downcast_ref::<HierarchyError>().expect("unexpected error").kind());
+    });
+
+    let a = world.spawn(Node);
+    let b = world.spawn(Node);
+    let c = world.spawn(Node);
+
+    world.add_child(a, c);
+    world.add_child(b, c);
+    assert_eq!(world.parent(c), Some(b));
+    assert!(world.children(a).is_empty());
+    assert_eq!(world.children(b), &[c]);
+
+    world.set_parent(c, a);
+    world.set_parent(c, a);
+    assert_eq!(world.children(a), &[c]);
+    assert!(world.children(b).is_empty());
+
+    world.add_child(c, a);
+    world.add_child(a, a);
+    world.add_child(c, b);
+    world.add_child(b, a);
+    assert_eq!(world.resource::<Errors>().0, vec![
+        HierarchyErrorKind::Cycle { parent: c, child: a },
+        HierarchyErrorKind::SelfParent(a),
+        HierarchyErrorKind::Cycle { parent: b, child: a },
+    ]);
+    assert_eq!(world.ancestors(b).collect::<Vec<_>>(), vec![c, a]);
+
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands| {
+        commands.add_child(b, a);
+        commands.remove_parent(c);
+    });
+    schedule.run(&mut world);
+    assert_eq!(world.resource::<Errors>().0.len(), 4);
+    assert_eq!(world.parent(c), None);
+    assert!(world.children(a).is_empty());
+
+    world.despawn(c);
+    assert!(!world.is_alive(b));
+    assert!(world.is_alive(a));
+}
+
+#[test]
+fn despawn_policies() {
+    #[derive(Component)]
+    struct Node;
+
+    let mut world = World::default();
+    let spawn_tree = |world: &mut World| {
+        let [root, a, b, c, a1, a2, a1x] = std::array::from_fn(|_| world.spawn(Node));
+        world.add_child(root, a);
+        world.add_child(root, b);
+        world.add_child(root, c);
+        world.add_child(a, a1);
+        world.add_child(a, a2);
+        world.add_child(a1, a1x);
+        [root, a, b, c, a1, a2, a1x]
+    };
+
+    let [root, a, b, c, a1, a2, a1x] = spawn_tree(&mut world);
+    world.despawn_reparent_children(a);
+    assert_eq!(world.children(root), &[a1, a2, b, c]);
+    assert_eq!(world.parent(a1), Some(root));
+    assert_eq!(world.children(a1), &[a1x]);
+    world.despawn_reparent_children(root);
+    assert!([a1, a2, b, c].iter().all(|e| world.is_alive(*e) && world.parent(*e).is_none()));
+    for entity in [a1, a2, b, c, a1x] { world.despawn(entity); }
+
+    let [root, a, b, c, a1, a2, a1x] = spawn_tree(&mut world);
+    world.despawn_detach_children(a);
+    assert_eq!(world.children(root).len(), 2);
+    assert!(world.parent(a1).is_none() && world.parent(a2).is_none());
+    assert_eq!(world.parent(a1x), Some(a1));
+    world.despawn_recursive(root);
+    world.despawn_recursive(a1);
+    assert!([root, b, c, a1, a1x].iter().all(|e| !world.is_alive(*e)));
+    assert!(world.is_alive(a2));
+    world.despawn(a2);
+
+    world.set_hierarchy_despawn_policy(HierarchyDespawnPolicy::DetachChildren);
+    let [root, a, b, c, a1, a2, a1x] = spawn_tree(&mut world);
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands| {
+        commands.despawn(a1);
+        commands.despawn_recursive(a);
+    });
+    schedule.run(&mut world);
+    assert!(!world.is_alive(a) && !world.is_alive(a1) && !world.is_alive(a2));
+    assert!(world.is_alive(a1x) && world.parent(a1x).is_none());
+    assert_eq!(world.children(root), &[b, c]);
+    world.despawn(root);
+    assert!(world.is_alive(b) && world.is_alive(c));
+}
+
+#[test]
+fn ordered_children() {
+    #[derive(Component)]
+    struct Order(i32);
+
+    let mut world = World::default();
+    let root = world.spawn(Order(0));
+    let [a, b, c, d] = [3, 1, 2, 1].map(|order| world.spawn(Order(order)));
+    world.add_child(root, a);
+    world.add_child(root, b);
+    world.add_child(root, c);
+    world.insert_child_at(root, 1, d);
+    assert_eq!(world.children(root), &[a, d, b, c]);
+
+    world.remove_child(root, a);
+    assert_eq!(world.children(root), &[d, b, c]);
+    world.insert_child_at(root, 100, a);
+    assert_eq!(world.children(root), &[d, b, c, a]);
+    world.insert_child_at(root, 0, a);
+    assert_eq!(world.children(root), &[a, d, b, c]);
+
+    world.move_child(root, a, 2);
+    assert_eq!(world.children(root), &[d, b, a, c]);
+    world.move_child(root, root, 0);
+    assert_eq!(world.children(root), &[d, b, a, c]);
+
+    world.sort_children_by_key(root, |world, child| world.get_component::<Order>(child).map(|order| order.0));
+    assert_eq!(world.children(root), &[d, b, c, a]);
+
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands| {
+        commands.sort_children_by_key(root, |_, child| std::cmp::Reverse(child.id()));
+        commands.move_child(root, a, 0);
+        commands.insert_child_at(root, 1, root);
+    });
+    let errors = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let errors_clone = errors.clone();
+    world.set_error_handler(move |_: error::ECSError| { errors_clone.fetch_add(1, std::sync::atomic::Ordering::Relaxed); });
+    schedule.run(&mut world);
+    assert_eq!(errors.load(std::sync::atomic::Ordering::Relaxed), 1);
+    let mut expected = vec![b, c, d];
+    expected.sort_by_key(|entity| std::cmp::Reverse(entity.id()));
+    expected.insert(0, a);
+    assert_eq!(world.children(root), expected.as_slice());
+
+    let children = world.query::<Children>().get(root).map(|children| children.to_vec());
+    assert_eq!(children.as_deref(), Some(expected.as_slice()));
+}
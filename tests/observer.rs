@@ -22,3 +22,50 @@ fn simple() {
 
     assert!(world.resource::<Count>().0 == 200);
 }
+
+#[test]
+fn lifecycle() {
+    #[derive(Resource, Default)]
+    struct Log(Vec<(&'static str, Entity)>);
+    #[derive(Component)]
+    struct Position(u32);
+    #[derive(Component)]
+    struct Velocity;
+
+    let mut world = World::default();
+    world.insert_resource(Log::default());
+    world.add_observer(|trigger: Trigger<OnAdd<Position>>, mut log: ResMut<Log>| {
+        log.0.push(("add", trigger.target().unwrap()));
+    });
+    world.add_observer(|trigger: Trigger<OnInsert<Position>>, mut log: ResMut<Log>| {
+        log.0.push(("insert", trigger.target().unwrap()));
+    });
+    world.add_observer(|trigger: Trigger<OnRemove<Position>>, query: Query<&Position>, mut log: ResMut<Log>| {
+        assert!(query.get(trigger.target().unwrap()).is_some(), "component removed before OnRemove");
+        log.0.push(("remove", trigger.target().unwrap()));
+    });
+    world.add_observer(|trigger: Trigger<OnDespawn>, mut log: ResMut<Log>| {
+        log.0.push(("despawn", trigger.target().unwrap()));
+    });
+
+    let a = world.spawn((Position(0), Velocity));
+    world.set_component(a, Position(1));
+    world.remove_component::<Position>(a);
+    world.remove_component::<Position>(a);
+    let b = world.spawn(Velocity);
+    world.set_component(b, Position(2));
+    world.despawn(b);
+    world.despawn(a);
+    let batch = world.spawn_batch((0..2).map(Position));
+
+    assert_eq!(world.resource::<Log>().0, [
+        ("add", a), ("insert", a),
+        ("insert", a),
+        ("remove", a),
+        ("add", b), ("insert", b),
+        ("despawn", b), ("remove", b),
+        ("despawn", a),
+        ("add", batch[0]), ("insert", batch[0]),
+        ("add", batch[1]), ("insert", batch[1]),
+    ]);
+}
@@ -0,0 +1,90 @@
+mod common;
+use ecs::*;
+
+#[derive(Component)]
+struct Ship;
+#[derive(Component)]
+struct Station;
+
+struct Targets;
+impl Relationship for Targets {}
+
+struct DockedAt;
+impl Relationship for DockedAt {
+    const EXCLUSIVE: bool = true;
+}
+
+struct OwnedBy;
+impl Relationship for OwnedBy {
+    const EXCLUSIVE: bool = true;
+    const DESPAWN_POLICY: RelationDespawnPolicy = RelationDespawnPolicy::DespawnSources;
+}
+
+#[test]
+fn lookups() {
+    let mut world = World::default();
+    let a = world.spawn(Ship);
+    let b = world.spawn(Ship);
+    let c = world.spawn(Ship);
+
+    world.relate::<Targets>(a, b);
+    world.relate::<Targets>(a, c);
+    world.relate::<Targets>(b, c);
+    world.relate::<Targets>(b, c);
+    assert_eq!(world.targets::<Targets>(a), &[b, c]);
+    assert_eq!(world.sources::<Targets>(c), &[a, b]);
+    assert!(world.targets::<DockedAt>(a).is_empty());
+
+    world.unrelate::<Targets>(a, c);
+    assert_eq!(world.targets::<Targets>(a), &[b]);
+    assert_eq!(world.sources::<Targets>(c), &[b]);
+
+    // a stale handle must not touch the relations of the entity reusing its id
+    world.despawn(c);
+    let d = world.spawn(Ship);
+    assert_eq!(d.id(), c.id());
+    world.relate::<Targets>(d, b);
+    world.unrelate::<Targets>(c, b);
+    assert_eq!(world.targets::<Targets>(d), &[b]);
+}
+
+#[test]
+fn exclusive() {
+    let mut world = World::default();
+    let ship = world.spawn(Ship);
+    let station_a = world.spawn(Station);
+    let station_b = world.spawn(Station);
+
+    world.relate::<DockedAt>(ship, station_a);
+    world.relate::<DockedAt>(ship, station_b);
+    assert_eq!(world.targets::<DockedAt>(ship), &[station_b]);
+    assert!(world.sources::<DockedAt>(station_a).is_empty());
+    assert_eq!(world.sources::<DockedAt>(station_b), &[ship]);
+}
+
+#[test]
+fn despawn_policy() {
+    let mut world = World::default();
+    let owner = world.spawn(Station);
+    let a = world.spawn(Ship);
+    let b = world.spawn(Ship);
+
+    world.relate::<OwnedBy>(a, owner);
+    world.relate::<OwnedBy>(b, owner);
+    world.relate::<DockedAt>(a, owner);
+    world.relate::<Targets>(b, a);
+
+    world.despawn(a);
+    assert!(world.targets::<Targets>(b).is_empty());
+    assert_eq!(world.sources::<OwnedBy>(owner), &[b]);
+    assert!(world.sources::<DockedAt>(owner).is_empty());
+
+    let mut schedule = Schedule::default();
+    schedule.add_system(move |mut commands: Commands| {
+        commands.relate::<Targets>(owner, b);
+        commands.despawn(owner);
+    });
+    schedule.run(&mut world);
+    assert!(!world.is_alive(b));
+    assert_eq!(world.query::<Entity>().iter().count(), 0);
+}
@@ -32,3 +32,24 @@ fn disallow_missing_resource() {
     schedule.add_system(|_: Res<A>| {});
     schedule.run(&mut world);
 }
+
+#[test]
+fn many_resources() {
+    use std::sync::atomic::Ordering;
+    use common::Marker;
+    let mut world = World::new(16).unwrap();
+    let mut schedule = Schedule::default();
+    macro_rules! insert_marker {
+        ($a:literal, $b:literal) => { world.insert_resource(Marker::<$a, $b>::default()); };
+    }
+    for_each_marker!(insert_marker; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19; [0 1 2 3 4 5 6 7 8 9]);
+
+    for _ in 0..16 {
+        schedule.add_system(|a: ResMut<Marker<19, 9>>, _: Res<Marker<0, 0>>| {
+            a.0.compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed).expect("Race condition occurred");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            a.0.store(false, Ordering::Relaxed);
+        });
+    }
+    schedule.run(&mut world);
+}